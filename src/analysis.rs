@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use time::{Duration, OffsetDateTime};
+
+use crate::cron::{self, Schedule};
+use crate::duration;
+
+pub struct Analysis {
+    pub start: OffsetDateTime,
+    pub end: OffsetDateTime,
+    pub total_runs: usize,
+    /// The busiest minutes with the indices of the schedules starting in them.
+    pub peaks: Vec<(OffsetDateTime, Vec<usize>)>,
+    pub minute_histogram: [usize; 60],
+    pub hour_histogram: [usize; 24],
+    /// Groups of schedules that start at exactly the same times in the window.
+    pub coinciding: Vec<Vec<usize>>,
+}
+
+/// Enumerates the occurrences of every schedule within `window` from
+/// `from_time` and counts how many start together.
+pub fn analyze(
+    schedules: &[&Schedule],
+    from_time: OffsetDateTime,
+    window: Duration,
+    num_peaks: usize,
+) -> Result<Analysis, String> {
+    let start = truncate_to_minute(from_time);
+    let end = start
+        .checked_add(window)
+        .ok_or_else(|| format!("the window of {} is too long", duration::format(window)))?;
+    // Rounded up, so that a run in the last part of a minute has a slot.
+    let minutes = (window.whole_seconds().max(0) as usize).div_ceil(60);
+    let mut per_minute = vec![0usize; minutes];
+    let mut minute_histogram = [0; 60];
+    let mut hour_histogram = [0; 24];
+    let mut fingerprints = HashMap::<Vec<usize>, Vec<usize>>::new();
+
+    for (i, schedule) in schedules.iter().enumerate() {
+        let mut indices = Vec::new();
+        for occurrence in cron::occurrences(start, schedule).take_while(|o| *o < end) {
+            let index = (occurrence - start).whole_minutes() as usize;
+            per_minute[index] += 1;
+            minute_histogram[occurrence.minute() as usize] += 1;
+            hour_histogram[occurrence.hour() as usize] += 1;
+            indices.push(index);
+        }
+        if !indices.is_empty() {
+            fingerprints.entry(indices).or_default().push(i);
+        }
+    }
+
+    let mut busiest = per_minute
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 1)
+        .collect::<Vec<_>>();
+    busiest.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(&b.0)));
+    let peaks = busiest
+        .into_iter()
+        .take(num_peaks)
+        .map(|(index, _)| {
+            let minute = start + Duration::minutes(index as i64);
+            let starting = (0..schedules.len())
+                .filter(|i| schedules[*i].matches(minute))
+                .collect();
+            (minute, starting)
+        })
+        .collect();
+
+    let mut coinciding = fingerprints
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .collect::<Vec<_>>();
+    coinciding.sort();
+
+    Ok(Analysis {
+        start,
+        end,
+        total_runs: per_minute.iter().sum(),
+        peaks,
+        minute_histogram,
+        hour_histogram,
+        coinciding,
+    })
+}
+
+fn truncate_to_minute(datetime: OffsetDateTime) -> OffsetDateTime {
+    datetime
+        .replace_second(0)
+        .and_then(|d| d.replace_nanosecond(0))
+        .unwrap()
+}
+
+/// Renders `(label, count)` rows as a horizontal bar chart.
+pub fn histogram(rows: &[(String, usize)]) -> String {
+    const WIDTH: usize = 40;
+    let max = rows.iter().map(|(_, count)| *count).max().unwrap_or(0);
+
+    rows.iter()
+        .map(|(label, count)| {
+            let bar = (count * WIDTH).checked_div(max).unwrap_or(0);
+            let bar = if bar == 0 && *count > 0 { 1 } else { bar };
+            format!("  {label} {:<WIDTH$} {count}\n", "#".repeat(bar))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn schedules(list: &[&str]) -> Vec<Schedule> {
+        list.iter()
+            .map(|s| Schedule::from_str(s).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn peaks_and_histograms() {
+        let schedules = schedules(&["0 * * * *", "0 */2 * * *", "30 * * * *"]);
+        let refs = schedules.iter().collect::<Vec<_>>();
+        let analysis = analyze(
+            &refs,
+            datetime!(2023-03-22 00:00:30 +0:00:00),
+            Duration::days(1),
+            2,
+        )
+        .unwrap();
+
+        assert_eq!(analysis.start, datetime!(2023-03-22 00:00:00 +0:00:00));
+        assert_eq!(analysis.total_runs, 24 + 12 + 24);
+        assert_eq!(
+            analysis.peaks,
+            vec![
+                (datetime!(2023-03-22 00:00:00 +0:00:00), vec![0, 1]),
+                (datetime!(2023-03-22 02:00:00 +0:00:00), vec![0, 1]),
+            ]
+        );
+        assert_eq!(analysis.minute_histogram[0], 36);
+        assert_eq!(analysis.minute_histogram[30], 24);
+        assert_eq!(analysis.hour_histogram[0], 3);
+        assert_eq!(analysis.hour_histogram[1], 2);
+    }
+
+    #[test]
+    fn coinciding_groups() {
+        let schedules = schedules(&["0 * * * *", "5 * * * *", "0 0-23 * * *", "0 */1 * * *"]);
+        let refs = schedules.iter().collect::<Vec<_>>();
+        let analysis = analyze(
+            &refs,
+            datetime!(2023-03-22 00:00:00 +0:00:00),
+            Duration::days(1),
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(analysis.coinciding, vec![vec![0, 2, 3]]);
+    }
+
+    #[test]
+    fn partial_minutes() {
+        let schedules = schedules(&["* * * * *"]);
+        let refs = schedules.iter().collect::<Vec<_>>();
+        let analysis = analyze(
+            &refs,
+            datetime!(2023-03-22 00:00:00 +0:00:00),
+            Duration::seconds(90),
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(analysis.total_runs, 2);
+        assert!(analyze(
+            &refs,
+            datetime!(2023-03-22 00:00:00 +0:00:00),
+            Duration::MAX,
+            0
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn render_histogram() {
+        let rows = [
+            ("a".to_string(), 4),
+            ("b".to_string(), 1),
+            ("c".to_string(), 0),
+        ];
+        let bars = histogram(&rows)
+            .lines()
+            .map(|line| line.matches('#').count())
+            .collect::<Vec<_>>();
+
+        assert_eq!(bars, vec![40, 10, 0]);
+    }
+}
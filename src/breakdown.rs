@@ -0,0 +1,129 @@
+use crate::cron::{self, DescribeOptions, Schedule};
+use crate::language::Field;
+
+const FIELDS: [Field; 5] = [
+    Field::Minute,
+    Field::Hour,
+    Field::DayOfMonth,
+    Field::Month,
+    Field::DayOfWeek,
+];
+// ANSI colours of the fields: cyan, green, yellow, magenta and blue.
+const COLORS: [&str; 5] = ["36", "32", "33", "35", "34"];
+const GAP: usize = 3;
+
+fn index(field: Field) -> usize {
+    FIELDS.iter().position(|f| *f == field).unwrap()
+}
+
+fn width(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Prints the schedule with every field above its name and the part of the
+/// description that comes from it, like:
+///
+/// ```text
+/// 30       9        *              *       1-5
+/// minute   hour     day-of-month   month   day-of-week
+/// at 09:30                                 on weekdays
+/// ```
+pub fn breakdown(schedule: &Schedule, options: &DescribeOptions, color: bool) -> String {
+    let values = [
+        &schedule.minute.value,
+        &schedule.hour.value,
+        &schedule.day_of_month.value,
+        &schedule.month.value,
+        &schedule.day_of_week.value,
+    ]
+    .map(|value| value.to_string());
+    let labels = FIELDS.map(|field| field.name().replace('_', "-"));
+    let fragments = cron::fragments(schedule, options);
+
+    let mut widths = [0; 5];
+    for i in 0..5 {
+        widths[i] = width(&values[i]).max(width(&labels[i]));
+    }
+    // Fragments describing several fields span their columns.
+    let span = |widths: &[usize; 5], first: usize, last: usize| {
+        widths[first..=last].iter().sum::<usize>() + GAP * (last - first)
+    };
+    for fragment in &fragments {
+        let first = index(fragment.fields[0]);
+        let last = index(*fragment.fields.last().unwrap());
+        let needed = width(&fragment.text);
+        let available = span(&widths, first, last);
+        if needed > available {
+            widths[last] += needed - available;
+        }
+    }
+
+    let cell = |text: &str, width: usize, color_index: usize| {
+        let padding = " ".repeat(width.saturating_sub(self::width(text)) + GAP);
+        if color {
+            format!("\x1b[{}m{text}\x1b[0m{padding}", COLORS[color_index])
+        } else {
+            format!("{text}{padding}")
+        }
+    };
+
+    let mut value_row = String::new();
+    let mut label_row = String::new();
+    for i in 0..5 {
+        value_row.push_str(&cell(&values[i], widths[i], i));
+        label_row.push_str(&cell(&labels[i], widths[i], i));
+    }
+
+    let mut fragment_row = String::new();
+    let mut column = 0;
+    for fragment in &fragments {
+        let first = index(fragment.fields[0]);
+        let last = index(*fragment.fields.last().unwrap());
+        for skipped in &widths[column..first] {
+            fragment_row.push_str(&" ".repeat(skipped + GAP));
+        }
+        fragment_row.push_str(&cell(&fragment.text, span(&widths, first, last), first));
+        column = last + 1;
+    }
+
+    [value_row, label_row, fragment_row]
+        .iter()
+        .map(|row| row.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breakdown_of(schedule: &str) -> String {
+        breakdown(
+            &Schedule::from_str(schedule).unwrap(),
+            &DescribeOptions::default(),
+            false,
+        )
+    }
+
+    #[test]
+    fn aligns_fragments() {
+        assert_eq!(
+            breakdown_of("5 * * * 0"),
+            "\
+5             *      *              *       0
+minute        hour   day-of-month   month   day-of-week
+at minute 5                                 on Sunday"
+        );
+    }
+
+    #[test]
+    fn clock_times_span_minute_and_hour() {
+        assert_eq!(
+            breakdown_of("30 9 * * 1-5"),
+            "\
+30       9      *              *       1-5
+minute   hour   day-of-month   month   day-of-week
+at 09:30                               on weekdays"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use time::Date;
+
+use crate::cron::{self, Days, Reason, Schedule};
+use crate::language::Language;
+
+// ANSI colours as in the breakdown: yellow for the day-of-month, blue for the
+// day-of-week and green for days both or the only restricting field select.
+const DAY_OF_MONTH_COLOR: &str = "33";
+const DAY_OF_WEEK_COLOR: &str = "34";
+const BOTH_COLOR: &str = "1;32";
+const WIDTH: usize = 7 * 4 - 1;
+
+fn marker(reason: Reason) -> char {
+    match reason {
+        Reason::DayOfMonth => 'd',
+        Reason::DayOfWeek => 'w',
+        Reason::Both => '*',
+    }
+}
+
+fn color_code(reason: Reason) -> &'static str {
+    match reason {
+        Reason::DayOfMonth => DAY_OF_MONTH_COLOR,
+        Reason::DayOfWeek => DAY_OF_WEEK_COLOR,
+        Reason::Both => BOTH_COLOR,
+    }
+}
+
+/// Prints `months` months from the one `from` is in as grids like `cal`'s,
+/// weeks starting on Sunday, with the days the schedule runs on in colour or
+/// followed by a marker, and a legend saying how often it runs on them. Month
+/// and weekday names are in `language`:
+///
+/// ```text
+///         December 2026
+/// Su  Mo  Tu  We  Th  Fr  Sa
+///          1*  2   3   4   5
+/// ```
+pub fn calendar(
+    schedule: &Schedule,
+    from: Date,
+    months: usize,
+    language: Language,
+    color: bool,
+) -> String {
+    let days = Days::new(schedule);
+    let mut grids = Vec::new();
+    let mut first = from.replace_day(1).unwrap();
+    for _ in 0..months {
+        grids.push(grid(&days, first, language, color));
+        first = match first.month() {
+            time::Month::December => first.replace_year(first.year() + 1).unwrap(),
+            _ => first,
+        }
+        .replace_month(first.month().next())
+        .unwrap();
+    }
+    grids.push(legend(schedule, from, color));
+    grids.join("\n\n")
+}
+
+fn grid(running: &Days, first: Date, language: Language, color: bool) -> String {
+    let title = format!("{} {}", language.month(first.month() as i32), first.year());
+    let mut lines = vec![format!("{title:^WIDTH$}").trim_end().to_string()];
+    lines.push(
+        (0..7)
+            .map(|i| language.weekday(i).chars().take(2).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("  "),
+    );
+
+    let mut cells = vec!["   ".to_string(); first.weekday().number_days_from_sunday() as usize];
+    let days = std::iter::successors(Some(first), |d| d.next_day())
+        .take_while(|d| d.month() == first.month());
+    for day in days {
+        cells.push(match (running.reason(day), color) {
+            (None, _) => format!("{:>2} ", day.day()),
+            (Some(reason), false) => format!("{:>2}{}", day.day(), marker(reason)),
+            (Some(reason), true) => format!("\x1b[{}m{:>2}\x1b[0m ", color_code(reason), day.day()),
+        });
+    }
+    for week in cells.chunks(7) {
+        lines.push(week.join(" ").trim_end().to_string());
+    }
+    lines.join("\n")
+}
+
+fn legend(schedule: &Schedule, from: Date, color: bool) -> String {
+    if cron::next_occurrence(from.midnight().assume_utc(), schedule).is_err() {
+        return "Never runs.".to_string();
+    }
+    let runs = schedule.minute.value.values(0, 59).len() * schedule.hour.value.values(0, 23).len();
+    let runs = match runs {
+        1 => "once".to_string(),
+        _ => format!("{runs} times"),
+    };
+    let key = |reason: Reason, text: &str| match color {
+        false => format!("{} {text}", marker(reason)),
+        true => format!("\x1b[{}m{text}\x1b[0m", color_code(reason)),
+    };
+    if !schedule.runs_on_either_day() {
+        return format!("{}, {runs} each", key(Reason::Both, "runs"));
+    }
+    format!(
+        "{}, {}, {}; {runs} each",
+        key(
+            Reason::Both,
+            "runs by both the day-of-month and the day-of-week"
+        ),
+        key(Reason::DayOfMonth, "by the day-of-month only"),
+        key(Reason::DayOfWeek, "by the day-of-week only"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn month_grid() {
+        let schedule = Schedule::from_str("30 9 * * 1-5").unwrap();
+
+        assert_eq!(
+            calendar(
+                &schedule,
+                date!(2026 - 10 - 19),
+                1,
+                Language::English,
+                false
+            ),
+            "       October 2026
+Su  Mo  Tu  We  Th  Fr  Sa
+                 1*  2*  3
+ 4   5*  6*  7*  8*  9* 10
+11  12* 13* 14* 15* 16* 17
+18  19* 20* 21* 22* 23* 24
+25  26* 27* 28* 29* 30* 31
+
+* runs, once each"
+        );
+    }
+
+    #[test]
+    fn union_of_days() {
+        let schedule = Schedule::from_str("0 */6 1,15 * MON").unwrap();
+        let calendar = calendar(
+            &schedule,
+            date!(2026 - 12 - 31),
+            2,
+            Language::English,
+            false,
+        );
+
+        assert!(calendar.contains("     December 2026\n"));
+        assert!(calendar.contains("     January 2027\n"));
+        assert!(calendar.contains("\n                     1d  2\n 3   4w  5   6"));
+        assert!(calendar.contains("\n10  11w 12  13  14  15d 16\n"));
+        assert!(calendar.ends_with(
+            "* runs by both the day-of-month and the day-of-week, d by the day-of-month only, w by the day-of-week only; 4 times each"
+        ));
+        assert_eq!(
+            Days::new(&schedule).reason(date!(2027 - 03 - 01)),
+            Some(Reason::Both)
+        );
+    }
+
+    #[test]
+    fn never_runs() {
+        let schedule = Schedule::from_str("0 0 31 2 *").unwrap();
+        let calendar = calendar(
+            &schedule,
+            date!(2026 - 10 - 19),
+            1,
+            Language::English,
+            false,
+        );
+
+        assert!(!calendar.contains('*'));
+        assert!(calendar.ends_with("\n\nNever runs."));
+    }
+
+    #[test]
+    fn names_in_language() {
+        let schedule = Schedule::from_str("0 9 * * 6").unwrap();
+        let calendar = calendar(
+            &schedule,
+            date!(2026 - 10 - 19),
+            1,
+            Language::Norwegian,
+            false,
+        );
+
+        assert!(calendar.starts_with("       oktober 2026\nsø  ma  ti  on  to  fr  lø\n"));
+    }
+
+    #[test]
+    fn starred_day_field() {
+        // Like in cron, a day-of-month starting with * only narrows the days.
+        let schedule = Schedule::from_str("0 9 */2 * MON").unwrap();
+        let calendar = calendar(
+            &schedule,
+            date!(2026 - 10 - 01),
+            1,
+            Language::English,
+            false,
+        );
+
+        assert!(calendar.contains("\n 4   5*  6   7   8   9  10\n11  12  13"));
+        assert!(calendar.contains("\n18  19* 20"));
+        assert!(calendar.ends_with("\n\n* runs, once each"));
+    }
+}
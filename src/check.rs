@@ -0,0 +1,344 @@
+use std::fmt;
+use std::ops::Range;
+
+use time::OffsetDateTime;
+
+use crate::cron::{self, DescribeOptions};
+use crate::crontab;
+use crate::datefmt::DateTimeFormat;
+use crate::lint::{self, Linter, Severity};
+use crate::policy::Policy;
+
+/// The rule of diagnostics for lines that aren't valid entries.
+pub const INVALID_ENTRY: &str = "invalid-entry";
+/// The rule of diagnostics for files that can't be read.
+pub const UNREADABLE_FILE: &str = "unreadable-file";
+/// The rule of diagnostics for `# explaincron: allow` annotations naming
+/// rules that don't exist.
+pub const UNKNOWN_RULE: &str = "unknown-rule";
+/// The rule of diagnostics for entries breaking a rule of the policy file.
+pub const POLICY_VIOLATION: &str = "policy-violation";
+
+/// The rules of the diagnostics that aren't lints, with their severities and
+/// summaries.
+pub const RULES: &[(&str, Severity, &str)] = &[
+    (
+        INVALID_ENTRY,
+        Severity::Error,
+        "line is neither a valid entry, a comment nor a variable",
+    ),
+    (UNREADABLE_FILE, Severity::Error, "file can't be read"),
+    (
+        UNKNOWN_RULE,
+        Severity::Warning,
+        "annotation allows a rule that doesn't exist",
+    ),
+    (
+        POLICY_VIOLATION,
+        Severity::Error,
+        "runs at times a rule of the policy file doesn't allow",
+    ),
+];
+
+/// Nothing worse than notes was found.
+pub const EXIT_OK: i32 = 0;
+/// Some entries are invalid, or break a rule reported as an error.
+pub const EXIT_ERRORS: i32 = 1;
+/// Every entry is valid, but some break a rule reported as a warning.
+pub const EXIT_WARNINGS: i32 = 2;
+
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub path: String,
+    /// The line and the columns on it, counting from 1, or nothing if the
+    /// diagnostic is about the whole file.
+    pub location: Option<(usize, Range<usize>)>,
+    pub severity: Severity,
+    pub rule: &'static str,
+    pub message: String,
+    /// What the entry's schedule means, for diagnostics about valid entries.
+    pub explanation: Option<String>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}:", self.path)?;
+        if let Some((line, columns)) = &self.location {
+            write!(fmt, "{line}:{}:", columns.start)?;
+        }
+        write!(
+            fmt,
+            " {}[{}]: {}",
+            self.severity.name(),
+            self.rule,
+            self.message
+        )
+    }
+}
+
+/// What to check the entries of a crontab for, besides being valid.
+pub struct Checks {
+    pub linter: Option<Linter>,
+    pub policy: Option<Policy>,
+    /// When to look for runs breaking the policy from.
+    pub from_time: OffsetDateTime,
+    /// How to show those runs.
+    pub format: DateTimeFormat,
+}
+
+/// Checks that every line of the crontab at `path` is valid, and that its
+/// entries break none of the lint rules or rules of the policy in `checks`.
+/// Lint rules named in a `# explaincron: allow <id>...` annotation above an
+/// entry aren't checked for it.
+pub fn check(path: &str, input: &str, checks: &Checks) -> Vec<Diagnostic> {
+    let (entries, invalid) = crontab::parse_all(input);
+    let mut diagnostics = invalid
+        .into_iter()
+        .map(|invalid| Diagnostic {
+            path: path.to_string(),
+            location: Some((invalid.line, invalid.columns)),
+            severity: Severity::Error,
+            rule: INVALID_ENTRY,
+            message: invalid.message,
+            explanation: None,
+        })
+        .collect::<Vec<_>>();
+
+    let explanation = |entry: &crontab::Entry| {
+        Some(format!(
+            "'{}': {}",
+            entry.schedule,
+            cron::describe(&entry.schedule, &DescribeOptions::default())
+        ))
+    };
+    if let Some(linter) = &checks.linter {
+        for entry in &entries {
+            let mut linter = linter.clone();
+            for id in entry.annotation("allow").into_iter().flat_map(|ids| {
+                ids.split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|id| !id.is_empty())
+            }) {
+                if let Err(message) = linter.set(id, None) {
+                    diagnostics.push(Diagnostic {
+                        path: path.to_string(),
+                        location: Some((entry.line, entry.columns[0].clone())),
+                        severity: Severity::Warning,
+                        rule: UNKNOWN_RULE,
+                        message,
+                        explanation: None,
+                    });
+                }
+            }
+            for lint in linter.lint(&entry.schedule) {
+                diagnostics.push(Diagnostic {
+                    path: path.to_string(),
+                    location: Some((entry.line, columns(entry, &lint))),
+                    severity: lint.severity,
+                    rule: lint.rule,
+                    message: lint.message,
+                    explanation: explanation(entry),
+                });
+            }
+        }
+    }
+    if let Some(policy) = &checks.policy {
+        for entry in &entries {
+            for violation in policy.evaluate(path, &entry.schedule, checks.from_time) {
+                diagnostics.push(Diagnostic {
+                    path: path.to_string(),
+                    location: Some((entry.line, entry.columns[0].start..entry.columns[4].end)),
+                    severity: violation.rule.severity,
+                    rule: POLICY_VIOLATION,
+                    message: format!(
+                        "{}, first at {}, which breaks '{}'",
+                        violation.problem,
+                        checks.format.format(violation.first),
+                        violation.rule.name
+                    ),
+                    explanation: explanation(entry),
+                });
+            }
+        }
+    }
+
+    diagnostics.sort_by_key(|d| d.location.as_ref().map(|(line, c)| (*line, c.start)));
+    diagnostics
+}
+
+/// The columns from the first to the last field the lint is about.
+pub fn columns(entry: &crontab::Entry, lint: &lint::Lint) -> Range<usize> {
+    let first = lint.fields.iter().map(|f| *f as usize).min().unwrap_or(0);
+    let last = lint.fields.iter().map(|f| *f as usize).max().unwrap_or(4);
+    entry.columns[first].start..entry.columns[last].end
+}
+
+pub fn exit_code(diagnostics: &[Diagnostic]) -> i32 {
+    match diagnostics.iter().map(|d| d.severity).max() {
+        Some(Severity::Error) => EXIT_ERRORS,
+        Some(Severity::Warning) => EXIT_WARNINGS,
+        _ => EXIT_OK,
+    }
+}
+
+/// The files matching `pattern`, in which `*` matches any characters and
+/// `?` any one character of a file or directory name. Patterns without them
+/// are paths as they are.
+pub fn expand(pattern: &str) -> Result<Vec<String>, String> {
+    if !pattern.contains(['*', '?']) {
+        return Ok(vec![pattern.to_string()]);
+    }
+
+    let root = if pattern.starts_with('/') { "/" } else { "" };
+    let mut paths = vec![root.to_string()];
+    for component in pattern.split('/').filter(|c| !c.is_empty()) {
+        let join = |dir: &str, name: &str| match dir {
+            "" => name.to_string(),
+            _ if dir.ends_with('/') => format!("{dir}{name}"),
+            _ => format!("{dir}/{name}"),
+        };
+        if !component.contains(['*', '?']) {
+            paths = paths.iter().map(|dir| join(dir, component)).collect();
+            continue;
+        }
+        let mut matching = Vec::new();
+        for dir in &paths {
+            let Ok(read) = std::fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+                continue;
+            };
+            let mut names = read
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                // Like a shell, only match hidden files if asked to.
+                .filter(|name| !name.starts_with('.') || component.starts_with('.'))
+                .filter(|name| glob_matches(component, name))
+                .collect::<Vec<_>>();
+            names.sort();
+            matching.extend(names.iter().map(|name| join(dir, name)));
+        }
+        paths = matching;
+    }
+
+    paths.retain(|path| std::path::Path::new(path).is_file());
+    if paths.is_empty() {
+        return Err(format!("no files match '{pattern}'"));
+    }
+    Ok(paths)
+}
+
+pub fn glob_matches(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match pattern.split_first() {
+            None => name.is_empty(),
+            Some(('*', rest)) => (0..=name.len()).any(|i| matches(rest, &name[i..])),
+            Some(('?', rest)) => !name.is_empty() && matches(rest, &name[1..]),
+            Some((c, rest)) => name.first() == Some(c) && matches(rest, &name[1..]),
+        }
+    }
+    matches(
+        &pattern.chars().collect::<Vec<_>>(),
+        &name.chars().collect::<Vec<_>>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn checks(linter: Option<Linter>) -> Checks {
+        Checks {
+            linter,
+            policy: None,
+            from_time: datetime!(2026-10-17 12:00:00 +0:00:00),
+            format: DateTimeFormat::default(),
+        }
+    }
+
+    const CRONTAB: &str = "MAILTO=ops
+# explaincron: allow top-of-hour
+0 2 * * * /usr/local/bin/backup
+*  2 * * * /usr/bin/poll
+*/0 * * * * /usr/bin/broken
+# explaincron: allow top-of-day
+15 0 31 * * /usr/bin/report
+";
+
+    #[test]
+    fn diagnostics() {
+        let diagnostics = check("crontab", CRONTAB, &checks(Some(Linter::default())))
+            .into_iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                "crontab:4:1: warning[every-minute]: runs every minute from 02:00 to 02:59; write '0 2 * * *' to run once at 02:00",
+                "crontab:5:1: error[invalid-entry]: a step must be at least 1",
+                "crontab:7:1: warning[unknown-rule]: 'top-of-day' is not a lint rule (every-minute, either-day, never-runs, missing-days, uneven-step, duplicate-values, top-of-hour)",
+                "crontab:7:6: warning[missing-days]: does not run in February, April, June, September, and November, which have fewer than 31 days",
+            ]
+        );
+        assert_eq!(
+            check("crontab", CRONTAB, &checks(Some(Linter::default())))[0].location,
+            Some((4, 1..5))
+        );
+    }
+
+    #[test]
+    fn exit_codes() {
+        let linting = checks(Some(Linter::default()));
+        assert_eq!(exit_code(&check("a", "0 2 * * * a", &linting)), EXIT_OK);
+        assert_eq!(
+            exit_code(&check("a", "* 2 * * * a", &linting)),
+            EXIT_WARNINGS
+        );
+        assert_eq!(
+            exit_code(&check("a", "* 2 * * * a", &checks(None))),
+            EXIT_OK
+        );
+        assert_eq!(
+            exit_code(&check("a", "* 2 * * * a\n0 0 30 2 * b", &linting)),
+            EXIT_ERRORS
+        );
+    }
+
+    #[test]
+    fn macros() {
+        let crontab = "@reboot /bin/start\n@daily /bin/a\n@hourly /bin/b\n";
+        let diagnostics = check("crontab", crontab, &checks(Some(Linter::default())));
+
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Note));
+        assert_eq!(exit_code(&diagnostics), EXIT_OK);
+    }
+
+    #[test]
+    fn policy_violations() {
+        let policy = Policy::from_str(
+            "[[rule]]\nname = \"quiet office hours\"\nforbid = \"* 8-17 * * 1-5\"\nseverity = \"warning\"",
+        )
+        .unwrap();
+        let checks = Checks {
+            policy: Some(policy),
+            ..checks(None)
+        };
+        let diagnostics = check("crontab", "0 2 * * * a\n 30 9 * * * b", &checks);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "crontab:2:2: warning[policy-violation]: runs at a forbidden time, first at 2026-10-19 09:30:00, which breaks 'quiet office hours'"
+        );
+        assert_eq!(diagnostics[0].location, Some((2, 2..12)));
+        assert_eq!(exit_code(&diagnostics), EXIT_WARNINGS);
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob_matches("*.cron", "backup.cron"));
+        assert!(glob_matches("job?", "job1"));
+        assert!(!glob_matches("job?", "job12"));
+        assert!(!glob_matches("*.cron", "backup.cron.bak"));
+        assert_eq!(expand("no/such/file").unwrap(), vec!["no/such/file"]);
+        assert!(expand("no/such/*.cron").is_err());
+    }
+}
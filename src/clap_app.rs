@@ -1,16 +1,106 @@
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg, SubCommand};
 
 use crate::cron;
+use crate::duration;
+use crate::lint;
+use crate::synthesis;
+use explaincron::schedule_set::ScheduleSet;
 
 const ABOUT: &str = "\nExplain cron schedules in human readable form.
 cron syntax:
     *	any value
     ,	value list separator
     -	range of values
-    /	step values";
+    /	step values
+
+Date patterns support %Y %m %d %H %I %M %S %p %A %a %B %b %z and %%.
+Use %-d, %-H and so on for numbers without zero padding.";
 
 const USAGE: &str = "explaincron [FLAGS] [ARGS]
-    explaincron '3-5 1/4 * FEB SUN'";
+    explaincron '3-5 1/4 * FEB SUN'
+    explaincron '30 9 * * 1-5' -n 5 --holidays holidays.ics
+    explaincron timeline /etc/crontab
+    explaincron analyze --window 1d /etc/crontab
+    explaincron rebalance /etc/crontab > crontab.new
+    explaincron overlap '*/10 * * * *' --duration 25m
+    explaincron depends '0 2 * * *' '30 2 * * *' --lag 30m
+    explaincron diff '*/15 9-17 * * MON-FRI' '0,15,30,45 9-17 * * 1-6'
+    explaincron normalize '0-59/15 1,2,3 * * 1-5/1'
+    explaincron combine '0 * * * * except 0 2 * * *'
+    explaincron calendar '0 9 1,15 * MON' --months 2
+    explaincron every 40m --at 08:10
+    explaincron write 'every weekday at 9:30am'
+    grep 'backup started' job.log | explaincron infer
+    explaincron lint '* 2 * * *' --allow top-of-hour
+    explaincron check --lint --policy policy.toml /etc/crontab '/etc/cron.d/*'";
+
+const REBALANCE_HELP: &str = "Jobs keep their frequency. Jobs at a single hour may move one hour
+either way. Constrain a job with comments directly above it:
+    # explaincron: keep         never move this job
+    # explaincron: hours 2      start within hour 2 (or a range, 1-3)
+    # explaincron: minutes 0-29 start within these minutes";
+
+const CHECK_HELP: &str = "Exits with 0 if everything is fine, 1 if there are errors and 2 if
+there are only warnings. Notes don't change the exit code.
+
+With --lint, an entry can skip rules named in a comment directly above it:
+    # explaincron: allow top-of-hour every-minute
+
+A policy file has a [[rule]] table for each rule, with a name and any of:
+    files = [\"*db*\"]             only check crontabs whose paths match
+    forbid = \"* 8-17 * * 1-5\"    never run at these times
+    min_interval = \"10m\"         never run again sooner than this
+    max_per_day = 24               never run more often on one day
+    severity = \"warning\"         report as note, warning or error (default)";
+
+const OVERLAP_HELP: &str = "Jobs in a crontab can declare their own runtime in a comment directly
+above them, which takes precedence over --duration:
+    # explaincron: duration 25m";
+
+fn parse_duration(s: &str) -> Result<(), String> {
+    duration::parse(s).map(|_| ())
+}
+
+fn parse_runtime(s: &str) -> Result<(), String> {
+    if duration::parse(s)?.is_positive() {
+        Ok(())
+    } else {
+        Err(format!("'{s}' is not a runtime longer than zero"))
+    }
+}
+
+/// Lags and windows up to the years occurrences are searched for, which
+/// keeps the times they reach within dates `time` handles.
+fn parse_span(s: &str) -> Result<time::Duration, String> {
+    let span = duration::parse(s)?;
+    if span > time::Duration::days(366 * cron::MAX_SEARCH_YEARS as i64) {
+        return Err(format!(
+            "'{s}' is longer than {} years",
+            cron::MAX_SEARCH_YEARS
+        ));
+    }
+    Ok(span)
+}
+
+/// Windows counted in whole minutes, like those of `analyze` and `rebalance`.
+fn parse_minutes(s: &str) -> Result<(), String> {
+    let window = parse_span(s)?;
+    if window < time::Duration::MINUTE || window.whole_seconds() % 60 != 0 {
+        return Err(format!("'{s}' is not a whole number of minutes"));
+    }
+    Ok(())
+}
+
+/// Up to a hundred years, which keeps the last month within dates `time`
+/// handles.
+fn parse_months(s: &str) -> Result<(), String> {
+    match parse_usize(s)? {
+        1..=MAX_MONTHS => Ok(()),
+        _ => Err(format!("'{s}' is not between 1 and {MAX_MONTHS} months")),
+    }
+}
+
+const MAX_MONTHS: usize = 1200;
 
 fn parse_usize(s: &str) -> Result<usize, String> {
     s.parse::<usize>()
@@ -18,17 +108,68 @@ fn parse_usize(s: &str) -> Result<usize, String> {
 }
 
 pub fn app() -> App<'static, 'static> {
-    return App::new("explaincron")
+    App::new("explaincron")
         .version("0.1")
         .usage(USAGE)
         .author("Fredrik Pe <fredrikpei@gmail.com>")
         .about(ABOUT)
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .setting(AppSettings::ArgsNegateSubcommands)
         .arg(
             Arg::with_name("SCHEDULE")
                 .help("The cron schedule to explain.")
                 .required_unless("random")
-                .index(1)
-                .validator(|input| cron::Schedule::from_str(&input).map(|_| ())),
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("clock")
+                .long("clock")
+                .takes_value(true)
+                .global(true)
+                .possible_values(&["12", "24", "locale"])
+                .help("Show times on a 12 or 24-hour clock [default: from LC_TIME or LANG]"),
+        )
+        .arg(
+            Arg::with_name("date")
+                .long("date")
+                .takes_value(true)
+                .global(true)
+                .help(
+                    "Show dates as iso, long (Tuesday, March 3, 2026) or a pattern like %d.%m.%Y",
+                ),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .global(true)
+                .help("Show dates and times with a pattern like '%a %-d %b %-I:%M %p'"),
+        )
+        .arg(
+            Arg::with_name("lang")
+                .long("lang")
+                .takes_value(true)
+                .global(true)
+                .possible_values(&["en", "de", "fr", "es", "no"])
+                .help("Describe schedules in this language, whose month and weekday names the schedule to explain may use; warnings stay in English [default: from LC_MESSAGES or LANG]"),
+        )
+        .arg(
+            Arg::with_name("breakdown")
+                .short("b")
+                .long("breakdown")
+                .help("Show each field above the part of the description it makes up, in colour on a terminal"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .help("Describe each field literally instead of using clock times"),
+        )
+        .arg(
+            Arg::with_name("stats")
+                .short("s")
+                .long("stats")
+                .help("Show how often the schedule runs and the gaps between runs"),
         )
         .arg(
             Arg::with_name("random")
@@ -37,6 +178,15 @@ pub fn app() -> App<'static, 'static> {
                 .multiple(false)
                 .help("Output a random cron schedule"),
         )
+        .arg(
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .help("Print a sentence, or JSON with the parsed fields, description, warnings and occurrences"),
+        )
         .arg(
             Arg::with_name("num-next-occurrence")
                 .short("n")
@@ -45,5 +195,349 @@ pub fn app() -> App<'static, 'static> {
                 .multiple(false)
                 .validator(|input| parse_usize(&input).map(|_| ()))
                 .help("Show <n> next occurrences"),
-        );
+        )
+        .arg(
+            Arg::with_name("holidays")
+                .long("holidays")
+                .takes_value(true)
+                .value_name("FILE")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Mark occurrences on the days in this file of dates or iCalendar file, like holidays or change freezes"),
+        )
+        .arg(
+            Arg::with_name("skip-holidays")
+                .long("skip-holidays")
+                .requires("holidays")
+                .help("Show the next occurrences that aren't on those days instead"),
+        )
+        .subcommand(
+            SubCommand::with_name("timeline")
+                .about("Show the upcoming runs of every job in a crontab")
+                .arg(crontab_arg())
+                .arg(
+                    Arg::with_name("num-next-occurrence")
+                        .short("n")
+                        .default_value("10")
+                        .long("next-occurrence")
+                        .multiple(false)
+                        .validator(|input| parse_usize(&input).map(|_| ()))
+                        .help("Show <n> next runs across all jobs"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("analyze")
+                .about("Find load peaks and colliding jobs in a crontab")
+                .arg(crontab_arg())
+                .arg(window_arg())
+                .arg(
+                    Arg::with_name("top")
+                        .short("t")
+                        .long("top")
+                        .default_value("5")
+                        .validator(|input| parse_usize(&input).map(|_| ()))
+                        .help("Show the <top> busiest minutes"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("rebalance")
+                .about("Suggest start times that spread out the jobs in a crontab")
+                .after_help(REBALANCE_HELP)
+                .arg(crontab_arg())
+                .arg(window_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name("overlap")
+                .about("Find runs that start before earlier runs have finished")
+                .after_help(OVERLAP_HELP)
+                .arg(
+                    Arg::with_name("SCHEDULE")
+                        .help("The cron schedule to check.")
+                        .required_unless("crontab")
+                        .conflicts_with("crontab")
+                        .index(1)
+                        .validator(|input| cron::Schedule::from_str(&input).map(|_| ())),
+                )
+                .arg(
+                    Arg::with_name("crontab")
+                        .short("c")
+                        .long("crontab")
+                        .takes_value(true)
+                        .help("Check the jobs in a crontab file, or '-' for stdin"),
+                )
+                .arg(
+                    Arg::with_name("duration")
+                        .short("d")
+                        .long("duration")
+                        .takes_value(true)
+                        .validator(|input| parse_runtime(&input))
+                        .help("How long each run takes, e.g. 25m"),
+                )
+                .arg(
+                    Arg::with_name("job")
+                        .long("job")
+                        .takes_value(true)
+                        .requires("crontab")
+                        .help("Only check the job with this comment or command"),
+                )
+                .arg(
+                    Arg::with_name("with")
+                        .long("with")
+                        .takes_value(true)
+                        .requires("job")
+                        .help("Check whether <job> overlaps this other job"),
+                )
+                .arg(window_arg())
+                .arg(
+                    Arg::with_name("num-overlaps")
+                        .short("n")
+                        .default_value("5")
+                        .validator(|input| parse_usize(&input).map(|_| ()))
+                        .help("Show the first <n> overlapping runs"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("depends")
+                .about("Check that a job always runs some time after the job it depends on")
+                .arg(schedule_arg(
+                    "UPSTREAM",
+                    "The schedule of the job that must run first.",
+                    1,
+                ))
+                .arg(schedule_arg(
+                    "DOWNSTREAM",
+                    "The schedule of the job that depends on it.",
+                    2,
+                ))
+                .arg(
+                    Arg::with_name("lag")
+                        .short("l")
+                        .long("lag")
+                        .required(true)
+                        .takes_value(true)
+                        .validator(|input| parse_span(&input).map(|_| ()))
+                        .help("How long after the upstream run the downstream run must start"),
+                )
+                .arg(
+                    Arg::with_name("within")
+                        .long("within")
+                        .default_value("1d")
+                        .validator(|input| parse_span(&input).map(|_| ()))
+                        .help("How long after the upstream run the downstream run may start"),
+                )
+                .arg(window_arg().default_value("31d"))
+                .arg(
+                    Arg::with_name("num-violations")
+                        .short("n")
+                        .default_value("10")
+                        .validator(|input| parse_usize(&input).map(|_| ()))
+                        .help("Show the first <n> violations"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("Compare when two versions of a schedule run")
+                .arg(schedule_arg("OLD", "The old schedule.", 1))
+                .arg(schedule_arg("NEW", "The new schedule.", 2))
+                .arg(window_arg().default_value("366d"))
+                .arg(
+                    Arg::with_name("num-samples")
+                        .short("n")
+                        .default_value("5")
+                        .validator(|input| parse_usize(&input).map(|_| ()))
+                        .help("Show the first <n> runs gained and lost"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("normalize")
+                .about("Print the canonical form of a schedule")
+                .arg(schedule_arg("SCHEDULE", "The cron schedule to normalize.", 1))
+                .arg(
+                    Arg::with_name("names")
+                        .long("names")
+                        .help("Write months and days of the week as names, like JAN and MON"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("combine")
+                .about("Combine schedules with 'or', 'and' and 'except', as cron lines where possible")
+                .arg(
+                    Arg::with_name("EXPRESSION")
+                        .help("Schedules joined by 'or', 'and' and 'except', applied from left to right.")
+                        .required(true)
+                        .index(1)
+                        .validator(|input| ScheduleSet::from_str(&input).map(|_| ())),
+                )
+                .arg(
+                    Arg::with_name("num-next-occurrence")
+                        .short("n")
+                        .default_value("5")
+                        .long("next-occurrence")
+                        .validator(|input| parse_usize(&input).map(|_| ()))
+                        .help("Show <n> next occurrences"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("calendar")
+                .about("Show the days a schedule runs on in month calendars")
+                .arg(schedule_arg(
+                    "SCHEDULE",
+                    "The cron schedule, e.g. '0 9 1,15 * MON'.",
+                    1,
+                ))
+                .arg(
+                    Arg::with_name("months")
+                        .short("m")
+                        .long("months")
+                        .default_value("3")
+                        .validator(|input| parse_months(&input))
+                        .help("Show this many months from the current one"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("every")
+                .about("Write cron lines that run at a fixed interval")
+                .arg(
+                    Arg::with_name("INTERVAL")
+                        .help("How often to run, e.g. 90m or 2d.")
+                        .required(true)
+                        .index(1)
+                        .validator(|input| parse_duration(&input)),
+                )
+                .arg(
+                    Arg::with_name("at")
+                        .long("at")
+                        .takes_value(true)
+                        .default_value("00:00")
+                        .validator(|input| synthesis::parse_time_of_day(&input).map(|_| ()))
+                        .help("A time of day to run at, e.g. 08:10"),
+                )
+                .arg(
+                    Arg::with_name("between")
+                        .long("between")
+                        .takes_value(true)
+                        .validator(|input| synthesis::parse_window(&input).map(|_| ()))
+                        .help("Only run within these times of day, e.g. 08:00-18:00"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("write")
+                .about("Write the cron schedule for a description in English")
+                .arg(
+                    Arg::with_name("DESCRIPTION")
+                        .help("When to run, e.g. 'every 15 minutes between 9 and 5 on Mondays'.")
+                        .required(true)
+                        .multiple(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("infer")
+                .about("Guess the cron lines that ran at the given times")
+                .arg(
+                    Arg::with_name("TIMESTAMPS")
+                        .help("A file with a timestamp at the start of each line, or '-' for stdin.")
+                        .default_value("-")
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("lint")
+                .about("Find valid schedules that probably don't mean what they say")
+                .arg(
+                    Arg::with_name("SCHEDULE")
+                        .help("The cron schedule to check.")
+                        .required_unless("rules")
+                        .index(1)
+                        .validator(|input| cron::Schedule::from_str(&input).map(|_| ())),
+                )
+                .arg(rule_arg("allow", "Don't check the rule with this ID"))
+                .arg(rule_arg("warn", "Report the rule with this ID as a warning"))
+                .arg(rule_arg("deny", "Report the rule with this ID as an error"))
+                .arg(
+                    Arg::with_name("rules")
+                        .long("rules")
+                        .help("List the rules with their IDs and severities"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .takes_value(true)
+                        .possible_values(&["text", "json"])
+                        .default_value("text")
+                        .help("Print a line per finding, or JSON"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("check")
+                .about("Check that every entry in crontab files is valid")
+                .after_help(CHECK_HELP)
+                .arg(
+                    Arg::with_name("FILES")
+                        .help("Crontab files, patterns like '/etc/cron.d/*', or '-' for stdin.")
+                        .required(true)
+                        .multiple(true),
+                )
+                .arg(
+                    Arg::with_name("lint")
+                        .short("l")
+                        .long("lint")
+                        .help("Also report entries that break lint rules"),
+                )
+                .arg(
+                    Arg::with_name("policy")
+                        .short("p")
+                        .long("policy")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("Also report entries that break the rules of this TOML policy file"),
+                )
+                .arg(rule_arg("allow", "Don't check the rule with this ID").requires("lint"))
+                .arg(rule_arg("warn", "Report the rule with this ID as a warning").requires("lint"))
+                .arg(rule_arg("deny", "Report the rule with this ID as an error").requires("lint"))
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .takes_value(true)
+                        .possible_values(&["text", "sarif"])
+                        .default_value("text")
+                        .help("Print a line per problem, or a SARIF 2.1.0 log for code scanning"),
+                ),
+        )
+}
+
+fn rule_arg(name: &'static str, help: &'static str) -> Arg<'static, 'static> {
+    Arg::with_name(name)
+        .long(name)
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .value_name("ID")
+        .validator(|input| lint::rule(&input).map(|_| ()))
+        .help(help)
+}
+
+fn schedule_arg(name: &'static str, help: &'static str, index: u64) -> Arg<'static, 'static> {
+    Arg::with_name(name)
+        .help(help)
+        .required(true)
+        .index(index)
+        .validator(|input| cron::Schedule::from_str(&input).map(|_| ()))
+}
+
+fn window_arg() -> Arg<'static, 'static> {
+    Arg::with_name("window")
+        .short("w")
+        .long("window")
+        .default_value("7d")
+        .validator(|input| parse_minutes(&input))
+        .help("How far ahead to look, e.g. 1d or 2w")
+}
+
+fn crontab_arg() -> Arg<'static, 'static> {
+    Arg::with_name("CRONTAB")
+        .help("The crontab file to read, or '-' for stdin.")
+        .required(true)
+        .index(1)
 }
@@ -0,0 +1,182 @@
+use std::collections::BTreeSet;
+
+use time::{Duration, OffsetDateTime};
+
+use crate::cron::{self, join_oxford, Schedule};
+use crate::language::Language;
+
+// Times of day up to this many are listed in a summary.
+const MAX_TIMES: usize = 5;
+
+#[derive(Debug, PartialEq)]
+pub enum Relation {
+    Equivalent,
+    /// The new schedule runs at some of the old one's times, and no others.
+    Subset,
+    /// The new schedule runs at all of the old one's times, and others.
+    Superset,
+    Overlapping,
+    Disjoint,
+}
+
+/// Runs that only one of the schedules has.
+#[derive(Default)]
+pub struct Difference {
+    pub count: usize,
+    pub first: Vec<OffsetDateTime>,
+    /// Days of the week of the runs, from Sunday as 0.
+    weekdays: BTreeSet<u8>,
+    times: BTreeSet<(u8, u8)>,
+}
+
+impl Difference {
+    fn add(&mut self, run: OffsetDateTime, max_samples: usize) {
+        self.count += 1;
+        if self.first.len() < max_samples {
+            self.first.push(run);
+        }
+        self.weekdays
+            .insert(run.weekday().number_days_from_sunday());
+        self.times.insert((run.hour(), run.minute()));
+    }
+
+    /// When the runs happen, like "on Saturday at 09:00".
+    pub fn summary(&self) -> String {
+        let mut summary = Vec::new();
+        if self.weekdays.len() < 7 {
+            let mut weekdays = self.weekdays.iter().copied().collect::<Vec<_>>();
+            weekdays.sort_by_key(|day| (day + 6) % 7);
+            summary.push(format!(
+                "on {}",
+                join_oxford(&weekdays, |day| Language::English
+                    .weekday(*day as i32)
+                    .to_string())
+            ));
+        }
+        if self.times.len() <= MAX_TIMES {
+            let times = self.times.iter().copied().collect::<Vec<_>>();
+            summary.push(format!(
+                "at {}",
+                join_oxford(&times, |(hour, minute)| format!("{hour:0>2}:{minute:0>2}"))
+            ));
+        } else {
+            summary.push(format!("at {} times of day", self.times.len()));
+        }
+        summary.join(" ")
+    }
+}
+
+pub struct Comparison {
+    pub relation: Relation,
+    /// Whether the schedules were found equivalent by their canonical form,
+    /// rather than only within the window.
+    pub identical: bool,
+    pub common: usize,
+    pub gained: Difference,
+    pub lost: Difference,
+}
+
+/// Compares the runs of an old and a new version of a schedule within
+/// `window`. Schedules with the same canonical form are equivalent at all
+/// times; otherwise the relation only holds within the window.
+pub fn compare(
+    old: &Schedule,
+    new: &Schedule,
+    from_time: OffsetDateTime,
+    window: Duration,
+    max_samples: usize,
+) -> Comparison {
+    let identical = old.normalize() == new.normalize();
+    let end = from_time + window;
+    let mut old_runs = cron::occurrences(from_time, old)
+        .take_while(|o| *o < end)
+        .peekable();
+    let mut new_runs = cron::occurrences(from_time, new)
+        .take_while(|o| *o < end)
+        .peekable();
+
+    let mut common = 0;
+    let mut gained = Difference::default();
+    let mut lost = Difference::default();
+    loop {
+        match (old_runs.peek(), new_runs.peek()) {
+            (None, None) => break,
+            (Some(o), Some(n)) if o == n => {
+                common += 1;
+                old_runs.next();
+                new_runs.next();
+            }
+            (Some(o), Some(n)) if o < n => lost.add(old_runs.next().unwrap(), max_samples),
+            (Some(_), None) => lost.add(old_runs.next().unwrap(), max_samples),
+            _ => gained.add(new_runs.next().unwrap(), max_samples),
+        }
+    }
+
+    let relation = match (gained.count, lost.count) {
+        _ if identical => Relation::Equivalent,
+        (0, 0) => Relation::Equivalent,
+        (0, _) => Relation::Subset,
+        (_, 0) => Relation::Superset,
+        _ if common > 0 => Relation::Overlapping,
+        _ => Relation::Disjoint,
+    };
+
+    Comparison {
+        relation,
+        identical,
+        common,
+        gained,
+        lost,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn comparison(old: &str, new: &str) -> Comparison {
+        compare(
+            &Schedule::from_str(old).unwrap(),
+            &Schedule::from_str(new).unwrap(),
+            datetime!(2023-03-20 00:00:00 +0:00:00),
+            Duration::days(28),
+            3,
+        )
+    }
+
+    #[test]
+    fn equivalent_spellings() {
+        let result = comparison("*/15 9-17 * * MON-FRI", "0,15,30,45 9-17 * * 1-5");
+
+        assert_eq!(result.relation, Relation::Equivalent);
+        assert!(result.identical);
+        assert_eq!(result.gained.count, 0);
+    }
+
+    #[test]
+    fn additional_day() {
+        let result = comparison("0 9 * * 1-5", "0 9 * * 1-6");
+
+        assert_eq!(result.relation, Relation::Superset);
+        assert_eq!(result.gained.count, 4);
+        assert_eq!(
+            result.gained.first[0],
+            datetime!(2023-03-25 09:00:00 +0:00:00)
+        );
+        assert_eq!(result.gained.summary(), "on Saturday at 09:00");
+        assert_eq!(result.lost.count, 0);
+    }
+
+    #[test]
+    fn moved_runs() {
+        let result = comparison("0 9 * * *", "30 9 * * SAT,SUN");
+
+        assert_eq!(result.relation, Relation::Disjoint);
+        assert_eq!(result.lost.summary(), "at 09:00");
+        assert_eq!(result.gained.summary(), "on Saturday and Sunday at 09:30");
+
+        let result = comparison("0 9 * * *", "0 9 * * SAT,SUN");
+        assert_eq!(result.relation, Relation::Subset);
+    }
+}
@@ -1,11 +1,16 @@
 use time::ext::NumericalDuration;
 use time::OffsetDateTime;
 
-const MONTH_NAMES: &[&str] = &[
+use crate::datefmt::{self, Clock};
+use crate::language::{DayGroup, Field, Language};
+use crate::lint::{Linter, Severity};
+
+pub const MONTH_NAMES: &[&str] = &[
     "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
 ];
-const WEEK_DAY_NAMES: &[&str] = &["MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
+pub const WEEK_DAY_NAMES: &[&str] = &["MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Schedule {
     pub minute: Minute,
     pub hour: Hour,
@@ -28,22 +33,210 @@ impl Schedule {
         })
     }
 
-    pub fn to_string(&self) -> String {
+    /// Whether the schedule fires during the minute of `datetime`.
+    pub fn matches(&self, datetime: OffsetDateTime) -> bool {
+        let minute = datetime
+            .replace_second(0)
+            .and_then(|d| d.replace_nanosecond(0))
+            .unwrap();
+        next_occurrence(minute, self).is_ok_and(|next| next == minute)
+    }
+}
+
+impl std::fmt::Display for Schedule {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            fmt,
+            "{} {} {} {} {}",
+            self.minute.value,
+            self.hour.value,
+            self.day_of_month.value,
+            self.month.value,
+            self.day_of_week.value,
+        )
+    }
+}
+
+impl Schedule {
+    /// The canonical form of the schedule: the shortest way to write every
+    /// field, with lists sorted and deduplicated, ranges and steps collapsed
+    /// and Sunday written as 0. Schedules that run at the same times and
+    /// differ only in spelling have the same canonical form.
+    pub fn normalize(&self) -> Schedule {
+        let day_of_month = self.day_of_month.value.values(1, 31);
+        let mut day_of_week = self
+            .day_of_week
+            .value
+            .values(0, 7)
+            .into_iter()
+            .map(|day| day % 7)
+            .collect::<Vec<_>>();
+        day_of_week.sort();
+        day_of_week.dedup();
+
+        // Restricting both days runs on either of them, so if one of them is
+        // every day the schedule runs every day. Otherwise the fields must
+        // start with `*` exactly when they did, which decides whether the
+        // schedule runs on either day or on both.
+        let either = self.runs_on_either_day();
+        let day_field = |field: &Value, set: &[i32], min, max| match either {
+            true => Value::from_values(set, min, max).without_star(min),
+            false if field.starts_with_star() => Value::from_values_with_star(set, min, max),
+            false => Value::from_values(set, min, max),
+        };
+        let (day_of_month, day_of_week) =
+            if either && (day_of_month.len() == 31 || day_of_week.len() == 7) {
+                (Value::Wildcard, Value::Wildcard)
+            } else {
+                (
+                    day_field(&self.day_of_month.value, &day_of_month, 1, 31),
+                    day_field(&self.day_of_week.value, &day_of_week, 0, 6),
+                )
+            };
+
+        Schedule {
+            minute: Minute {
+                value: Value::from_values(&self.minute.value.values(0, 59), 0, 59),
+            },
+            hour: Hour {
+                value: Value::from_values(&self.hour.value.values(0, 23), 0, 23),
+            },
+            day_of_month: DayOfMonth {
+                value: day_of_month,
+            },
+            month: Month {
+                value: Value::from_values(&self.month.value.values(1, 12), 1, 12),
+            },
+            day_of_week: DayOfWeek { value: day_of_week },
+        }
+    }
+
+    /// Whether the schedule runs on the days either the day-of-month or the
+    /// day-of-week selects, rather than on the days both select. Like in
+    /// Vixie cron, that is when neither of them starts with `*`, so `*/2`
+    /// doesn't count as restricting the days.
+    pub fn runs_on_either_day(&self) -> bool {
+        !self.day_of_month.value.starts_with_star() && !self.day_of_week.value.starts_with_star()
+    }
+
+    /// Writes the schedule with month and weekday names, like
+    /// `0 9 * JAN-MAR MON-FRI`.
+    pub fn to_string_with_names(&self) -> String {
+        // SUN is 7, so ranges from Sunday can't be written with names.
+        let day_of_week = match self.day_of_week.value {
+            Value::Range(0, ..) => Value::List(self.day_of_week.value.values(0, 6)),
+            ref value => value.clone(),
+        };
         format!(
             "{} {} {} {} {}",
-            self.minute.value.to_string(),
-            self.hour.value.to_string(),
-            self.day_of_month.value.to_string(),
-            self.month.value.to_string(),
-            self.day_of_week.value.to_string(),
+            self.minute.value,
+            self.hour.value,
+            self.day_of_month.value,
+            self.month
+                .value
+                .format_with(|i| MONTH_NAMES[(i - 1) as usize].to_string()),
+            day_of_week.format_with(|i| WEEK_DAY_NAMES[(i + 6).rem_euclid(7) as usize].to_string()),
         )
     }
+
+    /// Whether the schedule runs at some time on `date`. Use `Days` to check
+    /// many dates.
+    pub fn runs_on(&self, date: time::Date) -> bool {
+        Days::new(self).contains(date)
+    }
+}
+
+/// The days of the month, days of the week and months a schedule runs on,
+/// worked out once to check many dates.
+pub struct Days {
+    day_of_month: [bool; 32],
+    day_of_week: [bool; 7],
+    month: [bool; 13],
+    either: bool,
 }
 
+impl Days {
+    pub fn new(schedule: &Schedule) -> Days {
+        let mut days = Days {
+            day_of_month: [false; 32],
+            day_of_week: [false; 7],
+            month: [false; 13],
+            either: schedule.runs_on_either_day(),
+        };
+        for day in schedule.day_of_month.value.values(1, 31) {
+            days.day_of_month[day as usize] = true;
+        }
+        // Sunday is both 0 and 7 (SUN).
+        for day in schedule.day_of_week.value.values(0, 7) {
+            days.day_of_week[day as usize % 7] = true;
+        }
+        for month in schedule.month.value.values(1, 12) {
+            days.month[month as usize] = true;
+        }
+        days
+    }
+
+    pub fn contains(&self, date: time::Date) -> bool {
+        self.reason(date).is_some()
+    }
+
+    /// Why the schedule runs on `date`, or nothing if it doesn't.
+    pub fn reason(&self, date: time::Date) -> Option<Reason> {
+        if !self.month[date.month() as usize] {
+            return None;
+        }
+        let day_of_month = self.day_of_month[date.day() as usize];
+        let day_of_week = self.day_of_week[date.weekday().number_days_from_sunday() as usize];
+        match (day_of_month, day_of_week) {
+            (true, true) => Some(Reason::Both),
+            _ if !self.either => None,
+            (true, false) => Some(Reason::DayOfMonth),
+            (false, true) => Some(Reason::DayOfWeek),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Why a schedule runs on a day.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reason {
+    /// Only the day-of-month selects it, while the day-of-week doesn't.
+    DayOfMonth,
+    /// Only the day-of-week selects it, while the day-of-month doesn't.
+    DayOfWeek,
+    /// Both fields select it, or the schedule runs on the days both select.
+    Both,
+}
+
+// Leap day schedules (`0 0 29 2 *`) can go eight years between occurrences.
+pub const MAX_SEARCH_YEARS: i32 = 8;
+
 pub fn next_occurrence(
     from_time: OffsetDateTime,
     schedule: &Schedule,
 ) -> Result<OffsetDateTime, String> {
+    // Occurrences are on whole minutes, so start from the next one.
+    let from_time = if from_time.second() != 0 || from_time.nanosecond() != 0 {
+        from_time
+            .replace_second(0)
+            .and_then(|d| d.replace_nanosecond(0))
+            .map_err(|e| format!("{}", e))?
+            .saturating_add(1.minutes())
+    } else {
+        from_time
+    };
+    find_next(from_time, schedule, from_time.year() + MAX_SEARCH_YEARS)
+}
+
+fn find_next(
+    from_time: OffsetDateTime,
+    schedule: &Schedule,
+    last_year: i32,
+) -> Result<OffsetDateTime, String> {
+    if from_time.year() > last_year {
+        return Err(format!("'{schedule}' has no occurrence before {last_year}"));
+    }
+
     let mut next = from_time;
 
     let (month, wrapped) = next_month(next.month() as i32, &schedule.month);
@@ -58,7 +251,7 @@ pub fn next_occurrence(
         next = next
             .replace_year(next.year() + if wrapped { 1 } else { 0 })
             .map_err(|e| format!("invalid year {}", e))?;
-        return next_occurrence(next, &schedule);
+        return find_next(next, schedule, last_year);
     }
 
     let current_day = next.day() as i32;
@@ -68,15 +261,19 @@ pub fn next_occurrence(
         (_, Value::Wildcard) => {
             next_day_of_month(current_day, max_days(next), &schedule.day_of_month)
         }
-        _ => next_day_union(next, &schedule.day_of_week, &schedule.day_of_month),
+        _ if schedule.runs_on_either_day() => {
+            next_day_union(next, &schedule.day_of_week, &schedule.day_of_month)
+        }
+        _ => next_day_of_both(next, schedule),
     };
 
     if day != next.day() as i32 {
         next = next.replace_second(0).unwrap();
         next = next.replace_hour(0).unwrap();
         next = next.replace_minute(0).unwrap();
-        next = next.replace_day(day as u8).unwrap();
-        if wrapped {
+        if wrapped || day > max_days(next) {
+            // Let the next round find the first matching day of the next month.
+            next = next.replace_day(1).unwrap();
             next = next
                 .replace_month(next.month().next())
                 .map_err(|e| format!("{}", e))?;
@@ -85,8 +282,10 @@ pub fn next_occurrence(
                     .replace_year(next.year() + 1)
                     .map_err(|e| format!("{}", e))?;
             }
+        } else {
+            next = next.replace_day(day as u8).unwrap();
         }
-        return next_occurrence(next, &schedule);
+        return find_next(next, schedule, last_year);
     }
 
     let (hour, wrapped) = next_hour(next.hour() as i32, &schedule.hour);
@@ -96,34 +295,50 @@ pub fn next_occurrence(
         next = next.replace_minute(0).unwrap();
         next = next
             .replace_hour(hour as u8)
-            .map_err(|_e| format!("date error 4"))?;
+            .map_err(|_e| "date error 4".to_string())?;
         if wrapped {
             next = next.saturating_add(1.days());
         }
-        return next_occurrence(next, &schedule);
+        return find_next(next, schedule, last_year);
     }
 
-    let wrapped = next.second() != 0;
-    next = next
-        .replace_second(0)
-        .map_err(|_e| format!("date error 1"))?;
-
-    let (minute, wrapped) = next_minute(
-        next.minute() as i32 + if wrapped { 1 } else { 0 },
-        &schedule.minute,
-    );
+    let (minute, wrapped) = next_minute(next.minute() as i32, &schedule.minute);
 
     if wrapped || minute != next.minute() as i32 {
         next = next.replace_minute(minute as u8).unwrap();
         if wrapped {
             next = next.saturating_add(1.hours());
         }
-        return next_occurrence(next, &schedule);
+        return find_next(next, schedule, last_year);
     }
 
     Ok(next)
 }
 
+/// Iterator over the occurrences of a schedule at or after a point in time.
+/// Ends early if the schedule stops firing.
+pub struct Occurrences<'a> {
+    schedule: &'a Schedule,
+    next: Option<OffsetDateTime>,
+}
+
+impl<'a> Iterator for Occurrences<'a> {
+    type Item = OffsetDateTime;
+
+    fn next(&mut self) -> Option<OffsetDateTime> {
+        let occurrence = next_occurrence(self.next?, self.schedule).ok();
+        self.next = occurrence.map(|o| o.saturating_add(1.seconds()));
+        occurrence
+    }
+}
+
+pub fn occurrences(from_time: OffsetDateTime, schedule: &Schedule) -> Occurrences<'_> {
+    Occurrences {
+        schedule,
+        next: Some(from_time),
+    }
+}
+
 fn next_minute(current: i32, minute: &Minute) -> (i32, bool) {
     next_value(current, 0, 59, &minute.value)
 }
@@ -163,7 +378,7 @@ fn max_days(datetime: OffsetDateTime) -> i32 {
                 28
             }
         }
-        _ => 31,
+        _ => 30,
     }
 }
 
@@ -206,6 +421,17 @@ fn next_day_union(
     (day2, wrapped2)
 }
 
+/// The next day this month both day fields select, or a day past the end
+/// of the month if there's none left.
+fn next_day_of_both(datetime: OffsetDateTime, schedule: &Schedule) -> (i32, bool) {
+    let days = Days::new(schedule);
+    let date = datetime.date();
+    std::iter::successors(Some(date), |d| d.next_day())
+        .take_while(|d| d.month() == date.month())
+        .find(|d| days.contains(*d))
+        .map_or((max_days(datetime) + 1, false), |d| (d.day() as i32, false))
+}
+
 fn next_month(current: i32, month: &Month) -> (time::Month, bool) {
     let (x, wrapped) = next_value(current, 1, 12, &month.value);
     let month = match x {
@@ -245,9 +471,10 @@ fn next_value(current: i32, min: i32, max: i32, value: &Value) -> (i32, bool) {
             Some(n) => (n, false),
             None => (*start, true),
         },
-        Value::List(ref list) => match list.iter().find(|i| current <= **i) {
+        // Lists keep the order they were written in.
+        Value::List(ref list) => match list.iter().filter(|i| current <= **i).min() {
             Some(n) => (*n, false),
-            None => (list[0], true),
+            None => (*list.iter().min().unwrap(), true),
         },
         Value::Single(single) => (*single, current > *single),
 
@@ -258,134 +485,196 @@ fn next_value(current: i32, min: i32, max: i32, value: &Value) -> (i32, bool) {
     }
 }
 
+// Minute and hour combinations up to this many are written as clock times.
+const MAX_CLOCK_TIMES: usize = 5;
+
+#[derive(Default)]
+pub struct DescribeOptions {
+    /// Describe every field on its own instead of using clock times and
+    /// names like "weekdays".
+    pub verbose: bool,
+    pub clock: Clock,
+    pub language: Language,
+}
+
+/// A piece of a description and the fields it describes.
+pub struct Fragment {
+    pub fields: Vec<Field>,
+    pub text: String,
+}
+
 pub fn human_readable(schedule: &Schedule) -> String {
-    let mut result = String::new();
-
-    match &schedule.minute.value {
-        Value::Step(start, step) => result.push_str(&format!(
-            "At every {}minute{}",
-            ordinal(*step),
-            match start {
-                Some(i) => format!(" from {i} through 59"),
-                None => "".to_string(),
-            }
-        )),
-        Value::Range(start, stop, step) => result.push_str(&format!(
-            "At every {}minute from {start} through {stop}",
-            step.map_or("".to_string(), |s| ordinal(s))
-        )),
-        Value::List(list) => result.push_str(&format!(
-            "At minute {}",
-            join_oxford(list, |i| i.to_string())
-        )),
-        Value::Single(single) => result.push_str(&format!("At minute {single}")),
-        Value::Wildcard => result.push_str(&format!("At every minute")),
-    }
+    describe(schedule, &DescribeOptions::default())
+}
 
-    match &schedule.hour.value {
-        Value::Step(start, step) => result.push_str(&format!(
-            " past every {}hour{}",
-            ordinal(*step),
-            match start {
-                Some(i) => format!(" from {i} through 23"),
-                None => "".to_string(),
-            }
-        )),
-        Value::Range(start, stop, step) => {
-            result.push_str(&format!(
-                " past every {}hour from {start} through {stop}",
-                step.map_or("".to_string(), |s| ordinal(s))
+pub fn describe(schedule: &Schedule, options: &DescribeOptions) -> String {
+    sentence(&fragments(schedule, options))
+}
+
+/// The description split by field. Clock times like "at 09:30" describe
+/// both the minute and the hour. Fields that don't restrict the schedule
+/// are left out, except the minute.
+pub fn fragments(schedule: &Schedule, options: &DescribeOptions) -> Vec<Fragment> {
+    let language = options.language;
+    let fragment = |field, text| Fragment {
+        fields: vec![field],
+        text,
+    };
+    let mut fragments = Vec::new();
+
+    match clock_times(schedule) {
+        Some(times) if !options.verbose => fragments.push(Fragment {
+            fields: vec![Field::Minute, Field::Hour],
+            text: language.at_times(
+                &times
+                    .into_iter()
+                    .map(|(hour, minute)| datefmt::time_of_day(hour, minute, options.clock))
+                    .collect::<Vec<_>>(),
+            ),
+        }),
+        _ => {
+            fragments.push(fragment(
+                Field::Minute,
+                field_description(Field::Minute, &schedule.minute.value, 59, language)
+                    .unwrap_or_else(|| language.every(Field::Minute, None)),
             ));
+            fragments.extend(
+                field_description(Field::Hour, &schedule.hour.value, 23, language)
+                    .map(|text| fragment(Field::Hour, text)),
+            );
         }
-        Value::List(list) => result.push_str(&format!(
-            " past hour {}",
-            join_oxford(list, |i| i.to_string())
-        )),
-        Value::Single(single) => result.push_str(&format!(" past hour {single}")),
-        Value::Wildcard => (),
     }
+    fragments.extend(
+        field_description(
+            Field::DayOfMonth,
+            &schedule.day_of_month.value,
+            31,
+            language,
+        )
+        .map(|text| fragment(Field::DayOfMonth, text)),
+    );
+    fragments.extend(
+        field_description(Field::Month, &schedule.month.value, 12, language)
+            .map(|text| fragment(Field::Month, text)),
+    );
+    fragments.extend(
+        day_of_week_description(schedule, options).map(|text| fragment(Field::DayOfWeek, text)),
+    );
+    fragments
+}
 
-    match &schedule.day_of_month.value {
-        Value::Step(start, step) => result.push_str(&format!(
-            " on every {}day-of-month{}",
-            ordinal(*step),
-            match start {
-                Some(i) => format!(" from {i} through 31"),
-                None => "".to_string(),
-            }
-        )),
-        Value::Range(start, stop, step) => result.push_str(&format!(
-            " on every {}day-of-month from {start} through {stop}",
-            step.map_or("".to_string(), |s| ordinal(s)),
-        )),
-        Value::List(ref list) => result.push_str(&format!(
-            " on day-of-month {}",
-            join_oxford(list, |i| i.to_string())
-        )),
-        Value::Single(single) => result.push_str(&format!(" on day-of-month {single}")),
-        Value::Wildcard => (),
+/// Joins fragments into a capitalized sentence.
+pub fn sentence(fragments: &[Fragment]) -> String {
+    capitalize(&phrase(fragments))
+}
+
+/// Joins fragments without capitalizing them, to use within a sentence.
+pub fn phrase(fragments: &[Fragment]) -> String {
+    fragments
+        .iter()
+        .map(|f| f.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Turns a phrase into a sentence.
+pub fn capitalize(phrase: &str) -> String {
+    let mut chars = phrase.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+/// Things about the schedule that are easy to get wrong: the warnings and
+/// errors of the default lint rules.
+pub fn warnings(schedule: &Schedule) -> Vec<String> {
+    Linter::default()
+        .lint(schedule)
+        .into_iter()
+        .filter(|lint| lint.severity >= Severity::Warning)
+        .map(|lint| lint.message)
+        .collect()
+}
+
+/// The `(hour, minute)` times of day the schedule fires at, if there are only
+/// a few of them.
+fn clock_times(schedule: &Schedule) -> Option<Vec<(i32, i32)>> {
+    let is_concrete = |value: &Value| matches!(value, Value::Single(_) | Value::List(_));
+    if !is_concrete(&schedule.minute.value) || !is_concrete(&schedule.hour.value) {
+        return None;
     }
 
-    match &schedule.month.value {
-        Value::Step(start, step) => result.push_str(&format!(
-            " in every {}month{}",
-            ordinal(*step),
-            match start {
-                Some(i) => format!(" from {} through December", month_string(*i)),
-                None => "".to_string(),
-            }
+    let minutes = schedule.minute.value.values(0, 59);
+    let hours = schedule.hour.value.values(0, 23);
+    if minutes.len() * hours.len() > MAX_CLOCK_TIMES {
+        return None;
+    }
+    Some(
+        hours
+            .iter()
+            .flat_map(|hour| minutes.iter().map(move |minute| (*hour, *minute)))
+            .collect(),
+    )
+}
+
+/// Describes one field, or nothing if it's a wildcard. Open-ended steps run
+/// through `max`.
+fn field_description(field: Field, value: &Value, max: i32, language: Language) -> Option<String> {
+    match value {
+        Value::Step(start, step) => Some(format!(
+            "{}{}",
+            language.every(field, Some(*step)),
+            start.map_or(String::new(), |start| language.range(field, start, max))
         )),
-        Value::Range(start, stop, step) => result.push_str(&format!(
-            " in every {}month from {} through {}",
-            step.map_or("".to_string(), |s| ordinal(s)),
-            month_string(*start),
-            month_string(*stop)
+        Value::Range(start, stop, step) => Some(format!(
+            "{}{}",
+            language.every(field, *step),
+            language.range(field, *start, *stop)
         )),
-        Value::List(list) => {
-            result.push_str(&format!(" in {}", join_oxford(list, |i| month_string(i))))
-        }
-        Value::Single(single) => result.push_str(&format!(" in {}", month_string(*single))),
-        Value::Wildcard => (),
+        Value::List(list) => Some(language.at(field, list)),
+        Value::Single(single) => Some(language.at(field, &[*single])),
+        Value::Wildcard => None,
     }
+}
 
-    let day_of_week_prefix = match schedule.day_of_month.value {
-        Value::Step(None, _) => "if it's ",
+fn day_of_week_description(schedule: &Schedule, options: &DescribeOptions) -> Option<String> {
+    let value = &schedule.day_of_week.value;
+    let description = match day_of_week_group(value) {
+        Some(group) if !options.verbose => options.language.day_group(group).to_string(),
+        // Sunday is both 0 and 7 (SUN), so steps run through 7.
+        _ => field_description(Field::DayOfWeek, value, 7, options.language)?,
+    };
+    let connector = match schedule.day_of_month.value {
         Value::Wildcard => "",
-        _ => "and ",
+        _ => options
+            .language
+            .day_of_week_connector(!schedule.runs_on_either_day()),
     };
-    match &schedule.day_of_week.value {
-        Value::Step(start, step) => result.push_str(&format!(
-            " {}on every {}day-of-week{}",
-            day_of_week_prefix,
-            ordinal(*step),
-            match start {
-                Some(i) => format!(" from {} through Sunday", day_of_week_string(*i)),
-                None => "".to_string(),
-            }
-        )),
-        Value::Range(start, stop, step) => result.push_str(&format!(
-            " {}on every {}day-of-week from {} through {}",
-            day_of_week_prefix,
-            step.map_or("".to_string(), |s| ordinal(s)),
-            day_of_week_string(*start),
-            day_of_week_string(*stop)
-        )),
-        Value::List(list) => result.push_str(&format!(
-            " {}on {}",
-            day_of_week_prefix,
-            join_oxford(list, |i| day_of_week_string(i))
-        )),
-        Value::Single(single) => result.push_str(&format!(
-            " {}on {}",
-            day_of_week_prefix,
-            day_of_week_string(*single)
-        )),
-        Value::Wildcard => (),
+    Some(format!("{connector}{description}"))
+}
+
+/// Weekdays or weekends if the value selects exactly those days.
+fn day_of_week_group(value: &Value) -> Option<DayGroup> {
+    if matches!(value, Value::Wildcard | Value::Single(_)) {
+        return None;
+    }
+    // Sunday is both 0 and 7 (SUN).
+    let mut days = value
+        .values(0, 7)
+        .into_iter()
+        .map(|day| day % 7)
+        .collect::<Vec<_>>();
+    days.sort();
+    days.dedup();
+    match days.as_slice() {
+        [1, 2, 3, 4, 5] => Some(DayGroup::Weekdays),
+        [0, 6] => Some(DayGroup::Weekends),
+        _ => None,
     }
-    result.push_str(".");
-    result
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Minute {
     pub value: Value,
 }
@@ -398,6 +687,7 @@ impl Minute {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Hour {
     pub value: Value,
 }
@@ -410,6 +700,7 @@ impl Hour {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct DayOfMonth {
     pub value: Value,
 }
@@ -422,6 +713,7 @@ impl DayOfMonth {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Month {
     pub value: Value,
 }
@@ -434,6 +726,7 @@ impl Month {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct DayOfWeek {
     pub value: Value,
 }
@@ -446,7 +739,7 @@ impl DayOfWeek {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Step(Option<i32>, i32),
     Range(i32, i32, Option<i32>),
@@ -472,6 +765,75 @@ impl Value {
             Ok(Value::Single(elem_parser(value)?))
         }
     }
+
+    /// The sorted numbers the value selects between `min` and `max`.
+    pub fn values(&self, min: i32, max: i32) -> Vec<i32> {
+        let mut values = match self {
+            Value::Step(start, step) => (start.unwrap_or(min)..=max)
+                .step_by(*step as usize)
+                .collect(),
+            Value::Range(start, stop, step) => (*start..=*stop)
+                .step_by(step.map_or(1, |s| s as usize))
+                .collect(),
+            Value::List(list) => list.clone(),
+            Value::Single(single) => vec![*single],
+            Value::Wildcard => (min..=max).collect(),
+        };
+        values.sort();
+        values.dedup();
+        values
+    }
+
+    /// Whether the value is written starting with `*`, like `*` or `*/2`.
+    pub fn starts_with_star(&self) -> bool {
+        matches!(self, Value::Wildcard | Value::Step(None, _))
+    }
+
+    /// The value written without a leading `*`, with `*/2` as `1/2` for a
+    /// field starting at 1.
+    pub fn without_star(self, min: i32) -> Value {
+        match self {
+            Value::Step(None, step) => Value::Step(Some(min), step),
+            value => value,
+        }
+    }
+
+    /// Like `from_values`, but written starting with `*` if `set` is a step
+    /// from `min`, like those of `*/N` always are.
+    pub fn from_values_with_star(set: &[i32], min: i32, max: i32) -> Value {
+        match (Value::from_values(set, min, max), set) {
+            (value, _) if value.starts_with_star() => value,
+            // Steps can't be larger than the field's last value, so only
+            // fields starting at 1 can skip all but the first.
+            (_, [single]) if *single == min && min > 0 => Value::Step(None, max),
+            (_, [first, second]) if *first == min && second + (second - first) > max => {
+                Value::Step(None, second - first)
+            }
+            (value, _) => value,
+        }
+    }
+
+    /// The most compact value selecting exactly the sorted numbers in `set`.
+    pub fn from_values(set: &[i32], min: i32, max: i32) -> Value {
+        match set {
+            [] => Value::List(Vec::new()),
+            [single] => Value::Single(*single),
+            _ if set.len() as i32 == max - min + 1 => Value::Wildcard,
+            [first, .., last] => {
+                let step = set[1] - set[0];
+                let progression = set.windows(2).all(|w| w[1] - w[0] == step);
+                if progression && step == 1 {
+                    Value::Range(*first, *last, None)
+                } else if progression && set.len() > 2 && last + step > max {
+                    Value::Step(if *first == min { None } else { Some(*first) }, step)
+                } else if progression && set.len() > 2 {
+                    Value::Range(*first, *last, Some(step))
+                } else {
+                    Value::List(set.to_vec())
+                }
+            }
+        }
+    }
 }
 
 pub fn random_value(min: i32, max: i32) -> Value {
@@ -494,18 +856,31 @@ pub fn random_value(min: i32, max: i32) -> Value {
             }
         }
         2 => Value::Single(fastrand::i32(min..=max)),
-        3 => Value::Step(Some(fastrand::i32(min..=max)), fastrand::i32(min..=max)),
+        3 => Value::Step(
+            Some(fastrand::i32(min..=max)),
+            fastrand::i32(min.max(1)..=max),
+        ),
         _ => Value::Wildcard,
     }
 }
 
 impl std::fmt::Display for Value {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let str = match self {
-            Value::Step(Some(start), step) => format!("{start}/{step}"),
+        fmt.write_str(&self.format_with(|i| i.to_string()))
+    }
+}
+
+impl Value {
+    /// Writes the value with `name` for every number but steps, e.g. to
+    /// write `JAN-MAR` instead of `1-3`.
+    pub fn format_with(&self, name: impl Fn(i32) -> String) -> String {
+        match self {
+            Value::Step(Some(start), step) => format!("{}/{step}", name(*start)),
             Value::Step(None, step) => format!("*/{step}"),
             Value::Range(start, stop, step) => format!(
-                "{start}-{stop}{}",
+                "{}-{}{}",
+                name(*start),
+                name(*stop),
                 match step {
                     Some(s) => "/".to_string() + s.to_string().as_ref(),
                     None => "".to_string(),
@@ -513,15 +888,12 @@ impl std::fmt::Display for Value {
             ),
             Value::List(list) => list
                 .iter()
-                .map(|i| i.to_string())
+                .map(|i| name(*i))
                 .collect::<Vec<String>>()
                 .join(","),
-            Value::Single(i) => i.to_string(),
+            Value::Single(i) => name(*i),
             Value::Wildcard => "*".to_string(),
-        };
-        fmt.write_str(&str)?;
-
-        Ok(())
+        }
     }
 }
 
@@ -540,10 +912,10 @@ fn parse_stepped_range(
 ) -> Result<Value, String> {
     let split = input.split('/').collect::<Vec<&str>>();
     if split.len() > 2 {
-        return Err(format!("only one '/' is allowed"));
+        return Err("only one '/' is allowed".to_string());
     }
     let step = if split.len() == 2 {
-        Some(elem_parser(split[1])?)
+        Some(parse_step_size(split[1], elem_parser)?)
     } else {
         None
     };
@@ -560,7 +932,7 @@ fn parse_range(
         .map(elem_parser)
         .collect::<Result<Vec<_>, _>>()?;
     if l.len() != 2 {
-        return Err(format!("range can have only two elements"));
+        return Err("range can have only two elements".to_string());
     }
     if l[0] > l[1] {
         return Err(format!("range error {} is bigger than {}", l[0], l[1]));
@@ -572,30 +944,41 @@ fn parse_step(input: &str, elem_parser: fn(&str) -> Result<i32, String>) -> Resu
     let mut iter = input.split('/');
     let start = iter
         .next()
-        .ok_or(format!("step must have two elements"))
+        .ok_or_else(|| "step must have two elements".to_string())
         .and_then(|s| {
             if s == "*" {
                 Ok(None)
             } else {
-                elem_parser(s).map(|i| Some(i))
+                elem_parser(s).map(Some)
             }
         })?;
     let step = iter
         .next()
-        .ok_or(format!("step can only have two elements"))
-        .and_then(elem_parser)?;
+        .ok_or_else(|| "step can only have two elements".to_string())
+        .and_then(|s| parse_step_size(s, elem_parser))?;
     if iter.next().is_some() {
-        return Err(format!("step can only have two elements"));
+        return Err("step can only have two elements".to_string());
     }
 
     Ok(Value::Step(start, step))
 }
 
+/// Parses the number after a '/', which can't be 0.
+fn parse_step_size(
+    input: &str,
+    elem_parser: fn(&str) -> Result<i32, String>,
+) -> Result<i32, String> {
+    match elem_parser(input)? {
+        0 => Err("a step must be at least 1".to_string()),
+        step => Ok(step),
+    }
+}
+
 fn parse_minute(elem: &str) -> Result<i32, String> {
     elem.parse::<i32>()
         .map_err(|_| format!("'{elem}' is not a valid minute (0-59)"))
         .and_then(|i| {
-            if i <= 59 && i >= 0 {
+            if (0..=59).contains(&i) {
                 Ok(i)
             } else {
                 Err(format!("'{i}' is not a valid minute (0-59)"))
@@ -607,7 +990,7 @@ fn parse_hour(elem: &str) -> Result<i32, String> {
     elem.parse::<i32>()
         .map_err(|_| format!("'{elem}' is not a valid hour (0-23)"))
         .and_then(|i| {
-            if i <= 23 && i >= 0 {
+            if (0..=23).contains(&i) {
                 Ok(i)
             } else {
                 Err(format!("'{i}' is not a valid hour (0-23)"))
@@ -619,7 +1002,7 @@ fn parse_day_of_month(elem: &str) -> Result<i32, String> {
     elem.parse::<i32>()
         .map_err(|_| format!("'{elem}' is a valid day-of-month (1-31)"))
         .and_then(|i| {
-            if i <= 31 && i >= 1 {
+            if (1..=31).contains(&i) {
                 Ok(i)
             } else {
                 Err(format!("'{i}' is not a valid day-of-month (1-31)"))
@@ -634,7 +1017,7 @@ fn parse_month(elem: &str) -> Result<i32, String> {
             .parse::<i32>()
             .map_err(|_| format!("'{elem}' is not a valid month (1-12 or JAN-DEC)"))
             .and_then(|i| {
-                if i <= 12 && i >= 1 {
+                if (1..=12).contains(&i) {
                     Ok(i)
                 } else {
                     Err(format!("'{i}' is not a valid month (1-12 or JAN-DEC)"))
@@ -651,79 +1034,33 @@ fn parse_day_of_week(elem: &str) -> Result<i32, String> {
         Some(i) => Ok((i + 1) as i32),
         None => elem
             .parse::<i32>()
-            .map_err(|_| format!("'{elem}' is not a valid day-of-week (0-6 or MON-SUN)"))
+            .map_err(|_| format!("'{elem}' is not a valid day-of-week (0-7 or MON-SUN)"))
             .and_then(|i| {
-                if i <= 6 && i >= 0 {
+                // Sunday is both 0 and 7, like SUN.
+                if (0..=7).contains(&i) {
                     Ok(i)
                 } else {
-                    Err(format!("'{i}' is not a valid day-of-week (0-6 or MON-SUN)"))
+                    Err(format!("'{i}' is not a valid day-of-week (0-7 or MON-SUN)"))
                 }
             }),
     }
 }
 
-fn ordinal(i: i32) -> String {
-    if i == 1i32 {
-        return "".to_string();
-    }
-
-    let mut s = i.to_string();
-    if s.ends_with('1') && !s.ends_with("11") {
-        s.push_str("st ")
-    } else if s.ends_with('2') && !s.ends_with("12") {
-        s.push_str("nd ")
-    } else if s.ends_with('3') && !s.ends_with("13") {
-        s.push_str("rd ")
-    } else {
-        s.push_str("th ")
-    }
-    s
-}
-
-fn day_of_week_string(i: i32) -> String {
-    match i {
-        1 => "Monday".to_string(),
-        2 => "Tuesday".to_string(),
-        3 => "Wednesday".to_string(),
-        4 => "Thursday".to_string(),
-        5 => "Friday".to_string(),
-        6 => "Saturday".to_string(),
-        _ => "Sunday".to_string(),
-    }
-}
-
-fn month_string(i: i32) -> String {
-    match i {
-        2 => "February".to_string(),
-        3 => "March".to_string(),
-        4 => "April".to_string(),
-        5 => "May".to_string(),
-        6 => "June".to_string(),
-        7 => "July".to_string(),
-        8 => "August".to_string(),
-        9 => "September".to_string(),
-        10 => "October".to_string(),
-        11 => "November".to_string(),
-        12 => "December".to_string(),
-        _ => "January".to_string(),
-    }
-}
-
-fn join_oxford(vec: &Vec<i32>, to_string: fn(i32) -> String) -> String {
-    match vec.as_slice().split_last() {
+pub fn join_oxford<T>(vec: &[T], to_string: impl Fn(&T) -> String) -> String {
+    match vec.split_last() {
         None => String::new(),
-        Some((last, [])) => format!("{}", to_string(*last)),
-        Some((last, [i])) => format!("{} and {}", to_string(*i), to_string(*last)),
+        Some((last, [])) => to_string(last),
+        Some((last, [i])) => format!("{} and {}", to_string(i), to_string(last)),
         Some((last, first)) => format!(
             "{}, and {}",
             first.iter().fold(String::new(), |mut a, b| {
-                if a.len() > 0 {
+                if !a.is_empty() {
                     a.push_str(", ");
                 }
-                a.push_str(&to_string(*b));
+                a.push_str(&to_string(b));
                 a
             }),
-            to_string(*last)
+            to_string(last)
         ),
     }
 }
@@ -731,7 +1068,7 @@ fn join_oxford(vec: &Vec<i32>, to_string: fn(i32) -> String) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use time::macros::datetime;
+    use time::macros::{date, datetime};
 
     #[test]
     fn only_wildcards() {
@@ -773,6 +1110,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn step_zero() {
+        for schedule in ["*/0 * * * *", "0 0-12/0 * * *"] {
+            assert_eq!(
+                Schedule::from_str(schedule).err().unwrap(),
+                "a step must be at least 1"
+            );
+        }
+    }
+
     #[test]
     fn minute_range() {
         assert_eq!(
@@ -833,14 +1180,193 @@ mod tests {
     }
 
     #[test]
-    fn join_oxford_test() {
-        assert_eq!(join_oxford(&Vec::<i32>::new(), |i| i.to_string()), "");
-        assert_eq!(join_oxford(&vec![1], |i| i.to_string()), "1");
-        assert_eq!(join_oxford(&vec![1, 2], |i| i.to_string()), "1 and 2");
+    fn clock_time() {
+        assert_eq!(
+            human_readable(&Schedule::from_str("30 9 * * 1-5").unwrap()),
+            "At 09:30 on weekdays."
+        );
+        assert_eq!(
+            human_readable(&Schedule::from_str("0 17,9 * * *").unwrap()),
+            "At 09:00 and 17:00."
+        );
+        assert_eq!(
+            human_readable(&Schedule::from_str("0,30 8,12 1 * *").unwrap()),
+            "At 08:00, 08:30, 12:00, and 12:30 on day-of-month 1."
+        );
+        assert_eq!(
+            human_readable(&Schedule::from_str("0,30 8,12,16 * * *").unwrap()),
+            "At minute 0 and 30 past hour 8, 12, and 16."
+        );
+        assert_eq!(
+            human_readable(&Schedule::from_str("0 0 * * SAT,SUN").unwrap()),
+            "At 00:00 on weekends."
+        );
+    }
+
+    #[test]
+    fn twelve_hour_clock() {
+        let options = DescribeOptions {
+            clock: Clock::TwelveHour,
+            ..DescribeOptions::default()
+        };
+        assert_eq!(
+            describe(&Schedule::from_str("30 9,21 * * 1-5").unwrap(), &options),
+            "At 9:30 AM and 9:30 PM on weekdays."
+        );
+    }
+
+    #[test]
+    fn verbose() {
+        let options = DescribeOptions {
+            verbose: true,
+            ..DescribeOptions::default()
+        };
         assert_eq!(
-            join_oxford(&vec![1, 2, 3], |i| i.to_string()),
-            "1, 2, and 3"
+            describe(&Schedule::from_str("30 9 * * 1-5").unwrap(), &options),
+            "At minute 30 past hour 9 on every day-of-week from Monday through Friday."
         );
+        assert_eq!(
+            describe(&Schedule::from_str("0 0 * * 6,0").unwrap(), &options),
+            "At minute 0 past hour 0 on Saturday and Sunday."
+        );
+    }
+
+    #[test]
+    fn localized() {
+        let describe_in = |language, schedule| {
+            let options = DescribeOptions {
+                language,
+                ..DescribeOptions::default()
+            };
+            describe(&Schedule::from_str(schedule).unwrap(), &options)
+        };
+
+        assert_eq!(
+            describe_in(Language::German, "2/3 23 * * *"),
+            "In jeder 3. Minute von 2 bis 59 in Stunde 23."
+        );
+        assert_eq!(
+            describe_in(Language::French, "0 0 1 4-10/2 *"),
+            "À 00:00 le 1er du mois tous les 2 mois d'avril à octobre."
+        );
+        assert_eq!(
+            describe_in(Language::Spanish, "* * */2 * 3,6"),
+            "Cada minuto cada 2 días del mes pero solo los miércoles y sábados."
+        );
+        assert_eq!(
+            describe_in(Language::Norwegian, "30 9 3,15 * 1-5"),
+            "Klokken 09:30 den 3. og 15. i måneden og på hverdager."
+        );
+    }
+
+    #[test]
+    fn fragments_by_field() {
+        let fragments = fragments(
+            &Schedule::from_str("30 9 * * 1-5").unwrap(),
+            &DescribeOptions::default(),
+        );
+
+        assert_eq!(fragments.len(), 2);
+        assert_eq!(fragments[0].fields, vec![Field::Minute, Field::Hour]);
+        assert_eq!(fragments[0].text, "at 09:30");
+        assert_eq!(fragments[1].fields, vec![Field::DayOfWeek]);
+        assert_eq!(fragments[1].text, "on weekdays");
+    }
+
+    #[test]
+    fn schedule_warnings() {
+        let warnings = |s| warnings(&Schedule::from_str(s).unwrap());
+
+        assert!(warnings("0 9 * * 1-5").is_empty());
+        assert_eq!(
+            warnings("0 9 1 * 1"),
+            vec!["runs on days matching either the day-of-month or the day-of-week, not only on days matching both"]
+        );
+        assert!(warnings("0 9 */2 * 1").is_empty());
+        assert_eq!(
+            warnings("0 0 30 2 *"),
+            vec!["never runs, since no selected month has a day 30"]
+        );
+        assert_eq!(warnings("0 0 29 2 *"), vec!["only runs in leap years"]);
+        assert_eq!(
+            warnings("0 0 31 1-6 *"),
+            vec!["does not run in February, April, and June, which have fewer than 31 days"]
+        );
+        // February has a day 29 only in leap years.
+        assert_eq!(
+            warnings("0 0 29 * *"),
+            vec!["does not run in February except in leap years"]
+        );
+        assert_eq!(
+            warnings("0 0 29-31 * *"),
+            vec!["does not run in February except in leap years"]
+        );
+        // Every day written out is checked, not only the first.
+        assert_eq!(
+            warnings("0 0 1,29,31 1-4 *"),
+            vec![
+                "does not run on day 29 in February except in leap years",
+                "does not run on day 31 in February and April"
+            ]
+        );
+        assert_eq!(
+            warnings("0 0 1,30 2 *"),
+            vec!["does not run on day 30 in February"]
+        );
+        assert!(warnings("0 0 1,29 1,3 *").is_empty());
+    }
+
+    #[test]
+    fn uneven_step_warnings() {
+        let warnings = |s| warnings(&Schedule::from_str(s).unwrap());
+
+        assert!(warnings("*/15 */6 * */3 *").is_empty());
+        assert!(warnings("5/15 9-17/2 * * *").is_empty());
+        assert_eq!(
+            warnings("*/7 * * * *"),
+            vec!["'*/7' in the minute field starts over every hour, so runs are 7 minutes apart except 4 minutes from minute 56 to minute 0; a step that divides 60, like */6 or */10, runs evenly"]
+        );
+        assert_eq!(
+            warnings("0 */5 * * *"),
+            vec!["'*/5' in the hour field starts over every day, so runs are 5 hours apart except 4 hours from 20:00 to 00:00; a step that divides 24, like */4 or */6, runs evenly"]
+        );
+        assert_eq!(
+            warnings("*/25 * * * *")[0],
+            "'*/25' in the minute field starts over every hour, so runs are 25 minutes apart except 10 minutes from minute 50 to minute 0; a step that divides 60, like */20 or */30, runs evenly"
+        );
+        assert_eq!(
+            warnings("0 0 * */5 *"),
+            vec!["'*/5' in the month field starts over every year, so runs are 5 months apart except 2 months from November to January; a step that divides 12, like */4 or */6, runs evenly"]
+        );
+        assert_eq!(
+            warnings("0 0 * * */2"),
+            vec!["'*/2' in the day-of-week field starts over every week, so runs are 2 days apart except 1 day from Saturday to Sunday; list the days instead to make the gaps plain"]
+        );
+        assert_eq!(
+            warnings("0 0 */2 * *"),
+            vec!["'*/2' in the day-of-month field starts over every month, so runs are 2 days apart except 1 day from day 29 to day 1 in 29-day months and 1 day from day 31 to day 1 in 31-day months; no step divides every month, so to run evenly, run daily and check the date in the command, like [ $(( $(date +\\%s) / 86400 \\% 2 )) -eq 0 ], where cron needs each % written as \\%"]
+        );
+        assert_eq!(
+            warnings("0 0 */5 4 *"),
+            Vec::<String>::new(),
+            "30-day months end evenly for */5"
+        );
+        assert!(warnings("0 0 */2 * 1").is_empty());
+        // The next run after the last of the day is in another hour, day or
+        // month.
+        assert!(warnings("*/7 3 * * *").is_empty());
+        assert!(warnings("0 */5 * * 1").is_empty());
+        assert!(warnings("0 0 */2 1,3 *").is_empty());
+        assert_eq!(warnings("*/7 3,4 * * *").len(), 1);
+        assert_eq!(warnings("0 */5 * * 1,2").len(), 1);
+    }
+
+    #[test]
+    fn join_oxford_test() {
+        assert_eq!(join_oxford(&Vec::<i32>::new(), |i| i.to_string()), "");
+        assert_eq!(join_oxford(&[1], |i| i.to_string()), "1");
+        assert_eq!(join_oxford(&[1, 2], |i| i.to_string()), "1 and 2");
+        assert_eq!(join_oxford(&[1, 2, 3], |i| i.to_string()), "1, 2, and 3");
     }
 
     #[test]
@@ -958,4 +1484,204 @@ mod tests {
             next_occurrence(datetime, &schedule).unwrap()
         );
     }
+
+    #[test]
+    fn next_occ_starred_day_of_month() {
+        // */2 only narrows the Mondays to odd days, rather than adding days.
+        let datetime = datetime!(2026-10-06 00:00:00 +0:00:00);
+        let schedule = Schedule::from_str("0 9 */2 * MON").unwrap();
+
+        assert_eq!(
+            occurrences(datetime, &schedule).take(4).collect::<Vec<_>>(),
+            vec![
+                datetime!(2026-10-19 09:00:00 +0:00:00),
+                datetime!(2026-11-09 09:00:00 +0:00:00),
+                datetime!(2026-11-23 09:00:00 +0:00:00),
+                datetime!(2026-12-07 09:00:00 +0:00:00),
+            ]
+        );
+    }
+
+    #[test]
+    fn next_occ_unsorted_list() {
+        let datetime = datetime!(2023-03-22 12:02:00 +0:00:00);
+        let schedule = Schedule::from_str("5,1 * * * *").unwrap();
+
+        assert_eq!(
+            occurrences(datetime, &schedule).take(2).collect::<Vec<_>>(),
+            vec![
+                datetime!(2023-03-22 12:05:00 +0:00:00),
+                datetime!(2023-03-22 13:01:00 +0:00:00),
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize() {
+        let normalize = |s| Schedule::from_str(s).unwrap().normalize().to_string();
+
+        assert_eq!(normalize("5,1,5 * * * *"), "1,5 * * * *");
+        assert_eq!(normalize("0-59 */1 1-31 * 0-6"), "* * * * *");
+        assert_eq!(
+            normalize("0-59/15 1,2,3 * JAN-DEC 1-5/1"),
+            "*/15 1-3 * * 1-5"
+        );
+        assert_eq!(normalize("0 0 * * SUN"), "0 0 * * 0");
+        assert_eq!(normalize("0 0 * * 1,SUN,0"), "0 0 * * 0-1");
+        assert_eq!(normalize("0 0 * * 0,7"), "0 0 * * 0");
+        assert_eq!(normalize("0 0 * * 1-7"), "0 0 * * *");
+        assert_eq!(normalize("0 0 1/2 * *"), "0 0 */2 * *");
+        assert_eq!(normalize("0 0 5-15/5 * *"), "0 0 5-15/5 * *");
+        // Either day matches, and the day-of-week matches every day.
+        assert_eq!(normalize("0 0 1 * 0-6"), "0 0 * * *");
+        assert_eq!(normalize("0 0 1 * MON"), "0 0 1 * 1");
+        // `*/2` would run on Mondays that are odd days instead.
+        assert_eq!(normalize("0 0 1/2 * MON"), "0 0 1/2 * 1");
+        assert_eq!(normalize("0 0 1,15 * 0-6/2"), "0 0 1,15 * 0/2");
+        // Those still run on the days both fields select.
+        assert_eq!(normalize("0 0 */2 * MON"), "0 0 */2 * 1");
+        assert_eq!(normalize("0 0 */20 * MON"), "0 0 */20 * 1");
+        assert_eq!(normalize("0 0 */31 * MON"), "0 0 */31 * 1");
+        assert_eq!(normalize("0 0 1,15 * */6"), "0 0 1,15 * */6");
+    }
+
+    #[test]
+    fn names() {
+        let schedule = Schedule::from_str("0 9 * 1-3 1-5").unwrap();
+        assert_eq!(schedule.to_string_with_names(), "0 9 * JAN-MAR MON-FRI");
+
+        let schedule = Schedule::from_str("0 9 * */3 0,6").unwrap();
+        assert_eq!(schedule.to_string_with_names(), "0 9 * */3 SUN,SAT");
+
+        let schedule = Schedule::from_str("0 9 * * 0-2").unwrap();
+        assert_eq!(schedule.to_string_with_names(), "0 9 * * SUN,MON,TUE");
+
+        // SUN is written as 7, which reads back as Sunday.
+        let schedule = Schedule::from_str("0 9 * * MON-SUN").unwrap();
+        assert_eq!(schedule.to_string(), "0 9 * * 1-7");
+        assert_eq!(Schedule::from_str(&schedule.to_string()).unwrap(), schedule);
+        assert_eq!(
+            Schedule::from_str("0 9 * * 8").unwrap_err(),
+            "'8' is not a valid day-of-week (0-7 or MON-SUN)"
+        );
+    }
+
+    #[test]
+    fn runs_on() {
+        let schedule = Schedule::from_str("0 9 1 * SUN").unwrap();
+        // Either day matches.
+        assert!(schedule.runs_on(date!(2026 - 03 - 01)));
+        assert!(schedule.runs_on(date!(2026 - 04 - 01)));
+        assert!(schedule.runs_on(date!(2026 - 04 - 05)));
+        assert!(!schedule.runs_on(date!(2026 - 04 - 06)));
+
+        // Both days match when the day-of-month starts with *.
+        let schedule = Schedule::from_str("0 9 */2 * MON").unwrap();
+        assert!(schedule.runs_on(date!(2026 - 10 - 05)));
+        assert!(!schedule.runs_on(date!(2026 - 10 - 12)));
+        assert!(!schedule.runs_on(date!(2026 - 10 - 01)));
+
+        let schedule = Schedule::from_str("0 9 * FEB 1-5").unwrap();
+        assert!(schedule.runs_on(date!(2026 - 02 - 02)));
+        assert!(!schedule.runs_on(date!(2026 - 02 - 01)));
+        assert!(!schedule.runs_on(date!(2026 - 03 - 02)));
+    }
+
+    #[test]
+    fn next_occ_later_today() {
+        let datetime = datetime!(2026-10-19 00:29:13.5 +0:00:00);
+        let schedule = Schedule::from_str("0 2 * * *").unwrap();
+
+        assert_eq!(
+            datetime!(2026-10-19 02:00:00 +0:00:00),
+            next_occurrence(datetime, &schedule).unwrap()
+        );
+    }
+
+    #[test]
+    fn next_occ_skips_short_months() {
+        let datetime = datetime!(2023-04-02 12:00:00 +0:00:00);
+        let schedule = Schedule::from_str("0 0 31 * *").unwrap();
+
+        assert_eq!(
+            datetime!(2023-05-31 00:00:00 +0:00:00),
+            next_occurrence(datetime, &schedule).unwrap()
+        );
+    }
+
+    #[test]
+    fn next_occ_leap_day() {
+        let datetime = datetime!(2023-03-01 00:00:00 +0:00:00);
+        let schedule = Schedule::from_str("0 0 29 2 *").unwrap();
+
+        assert_eq!(
+            datetime!(2024-02-29 00:00:00 +0:00:00),
+            next_occurrence(datetime, &schedule).unwrap()
+        );
+    }
+
+    #[test]
+    fn next_occ_never() {
+        let datetime = datetime!(2023-03-01 00:00:00 +0:00:00);
+        let schedule = Schedule::from_str("0 0 30 2 *").unwrap();
+
+        assert!(next_occurrence(datetime, &schedule).is_err());
+    }
+
+    #[test]
+    fn value_sets() {
+        assert_eq!(Value::Step(Some(5), 20).values(0, 59), vec![5, 25, 45]);
+        assert_eq!(Value::Range(1, 9, Some(4)).values(0, 59), vec![1, 5, 9]);
+        assert_eq!(Value::List(vec![5, 1, 5]).values(0, 59), vec![1, 5]);
+
+        assert_eq!(Value::from_values(&[7], 0, 59), Value::Single(7));
+        assert_eq!(
+            Value::from_values(&[3, 4, 5], 0, 59),
+            Value::Range(3, 5, None)
+        );
+        assert_eq!(
+            Value::from_values(&[0, 15, 30, 45], 0, 59),
+            Value::Step(None, 15)
+        );
+        assert_eq!(
+            Value::from_values(&[5, 25, 45], 0, 59),
+            Value::Step(Some(5), 20)
+        );
+        assert_eq!(
+            Value::from_values(&[1, 5, 9], 0, 59),
+            Value::Range(1, 9, Some(4))
+        );
+        assert_eq!(
+            Value::from_values(&[17, 43], 0, 59),
+            Value::List(vec![17, 43])
+        );
+        assert_eq!(
+            Value::from_values(&(0..24).collect::<Vec<_>>(), 0, 23),
+            Value::Wildcard
+        );
+    }
+
+    #[test]
+    fn matches_minute() {
+        let schedule = Schedule::from_str("30 9 * * 1-5").unwrap();
+
+        assert!(schedule.matches(datetime!(2023-03-22 09:30:42 +0:00:00)));
+        assert!(!schedule.matches(datetime!(2023-03-22 09:31:00 +0:00:00)));
+        assert!(!schedule.matches(datetime!(2023-03-25 09:30:00 +0:00:00)));
+    }
+
+    #[test]
+    fn occurrences_iter() {
+        let datetime = datetime!(2023-03-22 12:12:55 +0:00:00);
+        let schedule = Schedule::from_str("0 22 * * 1-5").unwrap();
+
+        assert_eq!(
+            occurrences(datetime, &schedule).take(3).collect::<Vec<_>>(),
+            vec![
+                datetime!(2023-03-22 22:00:00 +0:00:00),
+                datetime!(2023-03-23 22:00:00 +0:00:00),
+                datetime!(2023-03-24 22:00:00 +0:00:00),
+            ]
+        );
+    }
 }
@@ -0,0 +1,378 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+
+use time::OffsetDateTime;
+
+use crate::cron::{self, DayOfMonth, DayOfWeek, Hour, Minute, Month, Schedule};
+
+const ANNOTATION_PREFIX: &str = "explaincron:";
+
+/// The schedules cron's `@` macros stand for. `@reboot` has none, as it runs
+/// when cron starts.
+const MACROS: [(&str, &str); 7] = [
+    ("@yearly", "0 0 1 1 *"),
+    ("@annually", "0 0 1 1 *"),
+    ("@monthly", "0 0 1 * *"),
+    ("@weekly", "0 0 * * 0"),
+    ("@daily", "0 0 * * *"),
+    ("@midnight", "0 0 * * *"),
+    ("@hourly", "0 * * * *"),
+];
+
+pub struct Entry {
+    pub line: usize,
+    /// The columns of the minute, hour, day-of-month, month and day-of-week
+    /// fields in the line, counting from 1.
+    pub columns: [Range<usize>; 5],
+    pub schedule: Schedule,
+    pub command: String,
+    pub comment: Option<String>,
+    /// Directives from `# explaincron: ...` comments above the entry.
+    pub annotations: Vec<String>,
+}
+
+impl Entry {
+    /// The comment directly above the entry, or its command if there is none.
+    pub fn label(&self) -> &str {
+        self.comment.as_deref().unwrap_or(&self.command)
+    }
+
+    /// The arguments of the annotation named `name`, e.g. `"1-3"` for
+    /// `# explaincron: hours 1-3`.
+    pub fn annotation(&self, name: &str) -> Option<&str> {
+        self.annotations.iter().find_map(|a| {
+            let (key, rest) = a.split_once(' ').unwrap_or((a, ""));
+            (key == name).then_some(rest.trim())
+        })
+    }
+
+    /// The entry's `line` with the schedule in place of its fields, keeping
+    /// the rest of the line as written.
+    pub fn with_schedule(&self, line: &str, schedule: &Schedule) -> String {
+        let byte = |column: usize| {
+            line.char_indices()
+                .nth(column - 1)
+                .map_or(line.len(), |(i, _)| i)
+        };
+        format!(
+            "{}{schedule}{}",
+            &line[..byte(self.columns[0].start)],
+            &line[byte(self.columns[4].end)..]
+        )
+    }
+}
+
+/// A line that isn't a valid entry.
+#[derive(Debug, PartialEq)]
+pub struct Invalid {
+    pub line: usize,
+    /// The columns of the offending part of the line, counting from 1.
+    pub columns: Range<usize>,
+    pub message: String,
+}
+
+pub fn parse(input: &str) -> Result<Vec<Entry>, String> {
+    let (entries, invalid) = parse_all(input);
+    match invalid.first() {
+        Some(invalid) => Err(format!("line {}: {}", invalid.line, invalid.message)),
+        None => Ok(entries),
+    }
+}
+
+/// Parses every valid entry, and collects the lines that aren't valid
+/// instead of stopping at the first.
+pub fn parse_all(input: &str) -> (Vec<Entry>, Vec<Invalid>) {
+    let mut entries = Vec::new();
+    let mut invalid = Vec::new();
+    let mut comment = None;
+    let mut annotations = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            comment = None;
+            annotations.clear();
+            continue;
+        }
+        if let Some(text) = trimmed.strip_prefix('#') {
+            match text.trim().strip_prefix(ANNOTATION_PREFIX) {
+                Some(annotation) => annotations.push(annotation.trim().to_string()),
+                None => comment = Some(text.trim().to_string()),
+            }
+            continue;
+        }
+        if is_variable(trimmed) {
+            continue;
+        }
+
+        let words = words(line);
+        let (comment, annotations) = (comment.take(), std::mem::take(&mut annotations));
+        if words[0].1 == "@reboot" {
+            continue;
+        }
+        // A macro stands for all five fields.
+        let fields = if words[0].1.starts_with('@') { 1 } else { 5 };
+        let schedule = if words.len() <= fields {
+            Err((
+                words[0].0.start..words[words.len() - 1].0.end,
+                match fields {
+                    1 => format!("entry needs a command after '{}'", words[0].1),
+                    _ => "entry needs 5 fields and a command".to_string(),
+                },
+            ))
+        } else if fields == 1 {
+            parse_macro(&words[0])
+        } else {
+            parse_schedule(&words)
+        };
+        match schedule {
+            Ok(schedule) => entries.push(Entry {
+                line: i + 1,
+                columns: [0, 1, 2, 3, 4].map(|field| words[field.min(fields - 1)].0.clone()),
+                schedule,
+                command: words[fields..]
+                    .iter()
+                    .map(|(_, word)| *word)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                comment,
+                annotations,
+            }),
+            Err((columns, message)) => invalid.push(Invalid {
+                line: i + 1,
+                columns,
+                message,
+            }),
+        }
+    }
+
+    (entries, invalid)
+}
+
+/// The words of the line with the columns they take up, counting from 1.
+fn words(line: &str) -> Vec<(Range<usize>, &str)> {
+    line.split_whitespace()
+        .map(|word| {
+            let offset = word.as_ptr() as usize - line.as_ptr() as usize;
+            let start = line[..offset].chars().count() + 1;
+            (start..start + word.chars().count(), word)
+        })
+        .collect()
+}
+
+fn parse_macro((columns, word): &(Range<usize>, &str)) -> Result<Schedule, (Range<usize>, String)> {
+    match MACROS.iter().find(|(name, _)| name == word) {
+        Some((_, schedule)) => Ok(Schedule::from_str(schedule).unwrap()),
+        None => Err((
+            columns.clone(),
+            format!(
+                "'{word}' is not a cron macro (@reboot, {})",
+                MACROS.map(|(name, _)| name).join(", ")
+            ),
+        )),
+    }
+}
+
+/// The schedule in the first five words, or the columns of the first field
+/// that isn't valid and why.
+fn parse_schedule(words: &[(Range<usize>, &str)]) -> Result<Schedule, (Range<usize>, String)> {
+    let at = |field: usize| move |message| (words[field].0.clone(), message);
+    Ok(Schedule {
+        minute: Minute::from_str(words[0].1).map_err(at(0))?,
+        hour: Hour::from_str(words[1].1).map_err(at(1))?,
+        day_of_month: DayOfMonth::from_str(words[2].1).map_err(at(2))?,
+        month: Month::from_str(words[3].1).map_err(at(3))?,
+        day_of_week: DayOfWeek::from_str(words[4].1).map_err(at(4))?,
+    })
+}
+
+/// The entry whose label is `name`, or else the first whose command contains it.
+pub fn find<'a>(entries: &'a [Entry], name: &str) -> Result<&'a Entry, String> {
+    entries
+        .iter()
+        .find(|e| e.label() == name)
+        .or_else(|| entries.iter().find(|e| e.command.contains(name)))
+        .ok_or_else(|| format!("no job named '{name}'"))
+}
+
+fn is_variable(line: &str) -> bool {
+    match line.split_once('=') {
+        Some((name, _)) => !name.trim().is_empty() && !name.trim().contains(' '),
+        None => false,
+    }
+}
+
+/// The next `count` occurrences across all entries in chronological order.
+/// Entries firing at the same time are ordered by their position in the crontab.
+pub fn timeline(
+    entries: &[Entry],
+    from_time: OffsetDateTime,
+    count: usize,
+) -> Vec<(OffsetDateTime, &Entry)> {
+    let mut streams = entries
+        .iter()
+        .map(|entry| cron::occurrences(from_time, &entry.schedule))
+        .collect::<Vec<_>>();
+    let mut heap = BinaryHeap::new();
+    for (i, stream) in streams.iter_mut().enumerate() {
+        if let Some(next) = stream.next() {
+            heap.push(Reverse((next, i)));
+        }
+    }
+
+    let mut result = Vec::with_capacity(count);
+    while result.len() < count {
+        let Some(Reverse((next, i))) = heap.pop() else {
+            break;
+        };
+        result.push((next, &entries[i]));
+        if let Some(following) = streams[i].next() {
+            heap.push(Reverse((following, i)));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    const CRONTAB: &str = "SHELL=/bin/sh
+# Nightly backup
+# explaincron: hours 1-3
+0 2 * * * /usr/local/bin/backup
+
+*/30 * * * *\t/usr/bin/poll --fast
+";
+
+    #[test]
+    fn parse_entries() {
+        let entries = parse(CRONTAB).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].line, 4);
+        assert_eq!(entries[0].label(), "Nightly backup");
+        assert_eq!(entries[0].annotation("hours"), Some("1-3"));
+        assert_eq!(entries[0].annotation("keep"), None);
+        assert_eq!(entries[1].line, 6);
+        assert_eq!(entries[1].label(), "/usr/bin/poll --fast");
+        assert!(entries[1].annotations.is_empty());
+        assert_eq!(entries[1].schedule.to_string(), "*/30 * * * *");
+    }
+
+    #[test]
+    fn find_entry() {
+        let entries = parse(CRONTAB).unwrap();
+
+        assert_eq!(find(&entries, "Nightly backup").unwrap().line, 4);
+        assert_eq!(find(&entries, "poll").unwrap().line, 6);
+        assert_eq!(
+            entries[1].with_schedule(
+                "*/30 * * * *\t/usr/bin/poll --fast",
+                &Schedule::from_str("15/30 * * * *").unwrap()
+            ),
+            "15/30 * * * *\t/usr/bin/poll --fast"
+        );
+        assert_eq!(
+            find(&entries, "cleanup").err().unwrap(),
+            "no job named 'cleanup'"
+        );
+    }
+
+    #[test]
+    fn parse_error_has_line() {
+        assert_eq!(
+            parse("# comment\n61 * * * * true").err().unwrap(),
+            "line 2: '61' is not a valid minute (0-59)"
+        );
+        assert_eq!(
+            parse("* * * * *").err().unwrap(),
+            "line 1: entry needs 5 fields and a command"
+        );
+    }
+
+    #[test]
+    fn invalid_lines() {
+        let (entries, invalid) = parse_all(
+            "0 2 * * * backup\n  61 * * * * true\n0 2 * JAN-FOO * a\n@dialy b\n@hourly\n",
+        );
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].columns[4], 9..10);
+        assert_eq!(
+            invalid,
+            vec![
+                Invalid {
+                    line: 2,
+                    columns: 3..5,
+                    message: "'61' is not a valid minute (0-59)".to_string()
+                },
+                Invalid {
+                    line: 3,
+                    columns: 7..14,
+                    message: "'FOO' is not a valid month (1-12 or JAN-DEC)".to_string()
+                },
+                Invalid {
+                    line: 4,
+                    columns: 1..7,
+                    message: "'@dialy' is not a cron macro (@reboot, @yearly, @annually, @monthly, @weekly, @daily, @midnight, @hourly)".to_string()
+                },
+                Invalid {
+                    line: 5,
+                    columns: 1..8,
+                    message: "entry needs a command after '@hourly'".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn macros() {
+        let entries = parse("@reboot /bin/start\n@daily /bin/a\n@annually\t/bin/b  --all").unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].line, 2);
+        assert_eq!(entries[0].schedule.to_string(), "0 0 * * *");
+        assert_eq!(entries[0].columns[4], 1..7);
+        assert_eq!(entries[1].schedule.to_string(), "0 0 1 1 *");
+        assert_eq!(entries[1].command, "/bin/b --all");
+        assert_eq!(
+            entries[1].with_schedule(
+                "@annually\t/bin/b  --all",
+                &Schedule::from_str("5 0 1 1 *").unwrap()
+            ),
+            "5 0 1 1 *\t/bin/b  --all"
+        );
+    }
+
+    #[test]
+    fn merged_timeline() {
+        let entries = parse(CRONTAB).unwrap();
+        let timeline = timeline(&entries, datetime!(2023-03-22 01:15:00 +0:00:00), 4)
+            .into_iter()
+            .map(|(time, entry)| (time, entry.label()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            timeline,
+            vec![
+                (
+                    datetime!(2023-03-22 01:30:00 +0:00:00),
+                    "/usr/bin/poll --fast"
+                ),
+                (datetime!(2023-03-22 02:00:00 +0:00:00), "Nightly backup"),
+                (
+                    datetime!(2023-03-22 02:00:00 +0:00:00),
+                    "/usr/bin/poll --fast"
+                ),
+                (
+                    datetime!(2023-03-22 02:30:00 +0:00:00),
+                    "/usr/bin/poll --fast"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use time::OffsetDateTime;
+
+const TWELVE_HOUR_REGIONS: &[&str] = &["US", "CA", "AU", "NZ", "PH", "IN"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Clock {
+    #[default]
+    TwentyFourHour,
+    TwelveHour,
+}
+
+impl Clock {
+    pub fn from_str(s: &str) -> Result<Clock, String> {
+        match s {
+            "24" => Ok(Clock::TwentyFourHour),
+            "12" => Ok(Clock::TwelveHour),
+            "locale" => Ok(Clock::from_env()),
+            _ => Err(format!("'{s}' is not a valid clock (12, 24 or locale)")),
+        }
+    }
+
+    /// The clock used in the region of LC_ALL, LC_TIME or LANG, e.g. 12-hour
+    /// for `en_US.UTF-8`.
+    pub fn from_env() -> Clock {
+        let locale = ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+            .unwrap_or_default();
+        Clock::from_locale(&locale)
+    }
+
+    fn from_locale(locale: &str) -> Clock {
+        let region = locale
+            .split(['.', '@'])
+            .next()
+            .and_then(|l| l.split_once('_'))
+            .map(|(_, region)| region);
+        match region {
+            Some(region) if TWELVE_HOUR_REGIONS.contains(&region) => Clock::TwelveHour,
+            _ => Clock::TwentyFourHour,
+        }
+    }
+}
+
+pub enum DateStyle {
+    Iso,
+    Long,
+    Pattern(String),
+}
+
+impl DateStyle {
+    pub fn from_str(s: &str) -> DateStyle {
+        match s {
+            "iso" => DateStyle::Iso,
+            "long" => DateStyle::Long,
+            pattern => DateStyle::Pattern(pattern.to_string()),
+        }
+    }
+
+    fn pattern(&self) -> &str {
+        match self {
+            DateStyle::Iso => "%Y-%m-%d",
+            DateStyle::Long => "%A, %B %-d, %Y",
+            DateStyle::Pattern(pattern) => pattern,
+        }
+    }
+}
+
+/// How to print occurrences. `pattern`, if given, overrides the date style
+/// and clock.
+pub struct DateTimeFormat {
+    pub clock: Clock,
+    pub date: DateStyle,
+    pub pattern: Option<String>,
+}
+
+impl Default for DateTimeFormat {
+    fn default() -> DateTimeFormat {
+        DateTimeFormat {
+            clock: Clock::TwentyFourHour,
+            date: DateStyle::Iso,
+            pattern: None,
+        }
+    }
+}
+
+impl DateTimeFormat {
+    pub fn format(&self, datetime: OffsetDateTime) -> String {
+        match &self.pattern {
+            Some(pattern) => strftime(datetime, pattern),
+            None => {
+                let time = match self.clock {
+                    Clock::TwentyFourHour => "%H:%M:%S",
+                    Clock::TwelveHour => "%-I:%M:%S %p",
+                };
+                strftime(datetime, &format!("{} {time}", self.date.pattern()))
+            }
+        }
+    }
+}
+
+/// A time of day like "09:30" or "9:30 AM".
+pub fn time_of_day(hour: i32, minute: i32, clock: Clock) -> String {
+    match clock {
+        Clock::TwentyFourHour => format!("{hour:0>2}:{minute:0>2}"),
+        Clock::TwelveHour => format!(
+            "{}:{minute:0>2} {}",
+            (hour + 11) % 12 + 1,
+            if hour < 12 { "AM" } else { "PM" }
+        ),
+    }
+}
+
+/// Formats a date with a subset of strftime: %Y %m %d %H %I %M %S %p %A %a
+/// %B %b %z and %%. A `-` after the `%` removes the zero padding of numbers.
+pub fn strftime(datetime: OffsetDateTime, pattern: &str) -> String {
+    let mut result = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        let mut spec = chars.next();
+        let padded = spec != Some('-');
+        if !padded {
+            spec = chars.next();
+        }
+        let number = |n: u8| {
+            if padded {
+                format!("{n:0>2}")
+            } else {
+                n.to_string()
+            }
+        };
+        match spec {
+            Some('Y') => result.push_str(&datetime.year().to_string()),
+            Some('m') => result.push_str(&number(datetime.month() as u8)),
+            Some('d') => result.push_str(&number(datetime.day())),
+            Some('H') => result.push_str(&number(datetime.hour())),
+            Some('I') => result.push_str(&number((datetime.hour() + 11) % 12 + 1)),
+            Some('M') => result.push_str(&number(datetime.minute())),
+            Some('S') => result.push_str(&number(datetime.second())),
+            Some('p') => result.push_str(if datetime.hour() < 12 { "AM" } else { "PM" }),
+            Some('A') => result.push_str(&datetime.weekday().to_string()),
+            Some('a') => result.push_str(&datetime.weekday().to_string()[..3]),
+            Some('B') => result.push_str(&datetime.month().to_string()),
+            Some('b') => result.push_str(&datetime.month().to_string()[..3]),
+            Some('z') => {
+                let (hours, minutes, _) = datetime.offset().as_hms();
+                let sign = if datetime.offset().is_negative() {
+                    '-'
+                } else {
+                    '+'
+                };
+                result.push_str(&format!("{sign}{:0>2}{:0>2}", hours.abs(), minutes.abs()));
+            }
+            Some('%') => result.push('%'),
+            Some(other) => {
+                result.push('%');
+                result.push(other);
+            }
+            None => result.push('%'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn clock_from_locale() {
+        assert_eq!(Clock::from_locale("en_US.UTF-8"), Clock::TwelveHour);
+        assert_eq!(Clock::from_locale("nb_NO.UTF-8"), Clock::TwentyFourHour);
+        assert_eq!(Clock::from_locale("C"), Clock::TwentyFourHour);
+    }
+
+    #[test]
+    fn times_of_day() {
+        assert_eq!(time_of_day(9, 30, Clock::TwentyFourHour), "09:30");
+        assert_eq!(time_of_day(9, 30, Clock::TwelveHour), "9:30 AM");
+        assert_eq!(time_of_day(0, 0, Clock::TwelveHour), "12:00 AM");
+        assert_eq!(time_of_day(12, 5, Clock::TwelveHour), "12:05 PM");
+        assert_eq!(time_of_day(23, 59, Clock::TwelveHour), "11:59 PM");
+    }
+
+    #[test]
+    fn datetime_formats() {
+        let datetime = datetime!(2026-03-03 21:05:00 -5:00);
+
+        assert_eq!(
+            DateTimeFormat::default().format(datetime),
+            "2026-03-03 21:05:00"
+        );
+        let format = DateTimeFormat {
+            clock: Clock::TwelveHour,
+            date: DateStyle::Long,
+            pattern: None,
+        };
+        assert_eq!(format.format(datetime), "Tuesday, March 3, 2026 9:05:00 PM");
+        let format = DateTimeFormat {
+            date: DateStyle::from_str("%d.%m.%Y"),
+            ..DateTimeFormat::default()
+        };
+        assert_eq!(format.format(datetime), "03.03.2026 21:05:00");
+    }
+
+    #[test]
+    fn strftime_patterns() {
+        let datetime = datetime!(2026-03-03 09:05:07 -5:00);
+
+        assert_eq!(
+            strftime(datetime, "%a %b %-d %-H:%M:%S %z"),
+            "Tue Mar 3 9:05:07 -0500"
+        );
+        assert_eq!(strftime(datetime, "100%% %q"), "100% %q");
+    }
+}
@@ -0,0 +1,106 @@
+use time::{Duration, OffsetDateTime};
+
+use crate::cron::{self, Schedule};
+
+pub struct Violation {
+    pub downstream: OffsetDateTime,
+    /// The last upstream run before the downstream run, if any.
+    pub last_upstream: Option<OffsetDateTime>,
+}
+
+/// Checks that every run of `downstream` within `window` is preceded by a
+/// run of `upstream` that started between `min_lag` and `max_lag` earlier.
+pub fn check(
+    upstream: &Schedule,
+    downstream: &Schedule,
+    min_lag: Duration,
+    max_lag: Duration,
+    from_time: OffsetDateTime,
+    window: Duration,
+) -> Vec<Violation> {
+    let end = from_time + window;
+    let upstream_runs = cron::occurrences(from_time - max_lag, upstream)
+        .take_while(|o| *o < end)
+        .collect::<Vec<_>>();
+
+    let mut violations = Vec::new();
+    let mut next_upstream = 0;
+    for downstream_run in cron::occurrences(from_time, downstream).take_while(|o| *o < end) {
+        while next_upstream < upstream_runs.len() && upstream_runs[next_upstream] <= downstream_run
+        {
+            next_upstream += 1;
+        }
+        let earlier = &upstream_runs[..next_upstream];
+        // Runs further back than `max_lag` can't satisfy it either.
+        let satisfied = earlier
+            .iter()
+            .rev()
+            .map(|upstream_run| downstream_run - *upstream_run)
+            .take_while(|lag| *lag <= max_lag)
+            .any(|lag| min_lag <= lag);
+        if !satisfied {
+            violations.push(Violation {
+                downstream: downstream_run,
+                last_upstream: earlier.last().copied(),
+            });
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn violations(upstream: &str, downstream: &str, min_lag: Duration) -> Vec<OffsetDateTime> {
+        check(
+            &Schedule::from_str(upstream).unwrap(),
+            &Schedule::from_str(downstream).unwrap(),
+            min_lag,
+            Duration::days(1),
+            datetime!(2023-03-01 00:00:00 +0:00:00),
+            Duration::days(31),
+        )
+        .into_iter()
+        .map(|v| v.downstream)
+        .collect()
+    }
+
+    #[test]
+    fn contract_holds() {
+        assert!(violations("0 2 * * *", "30 2 * * *", Duration::minutes(30)).is_empty());
+    }
+
+    #[test]
+    fn lag_too_short() {
+        assert_eq!(
+            violations("0 2 * * *", "20 2 1 * *", Duration::minutes(30)),
+            vec![datetime!(2023-03-01 02:20:00 +0:00:00)]
+        );
+    }
+
+    #[test]
+    fn union_adds_runs() {
+        // The downstream job also runs on the 1st, which is a Saturday in April.
+        let result = check(
+            &Schedule::from_str("0 2 * * 1-5").unwrap(),
+            &Schedule::from_str("30 2 1 * 1-5").unwrap(),
+            Duration::minutes(30),
+            Duration::hours(1),
+            datetime!(2023-03-25 00:00:00 +0:00:00),
+            Duration::days(10),
+        );
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].downstream,
+            datetime!(2023-04-01 02:30:00 +0:00:00)
+        );
+        assert_eq!(
+            result[0].last_upstream,
+            Some(datetime!(2023-03-31 02:00:00 +0:00:00))
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use time::Duration;
+
+/// Parses durations like `25m`, `1h30m` or `7d`. Supported units are
+/// s, m, h, d and w.
+pub fn parse(input: &str) -> Result<Duration, String> {
+    let err = || format!("'{input}' is not a valid duration (e.g. 30s, 25m, 1h30m, 7d)");
+    if input.is_empty() {
+        return Err(err());
+    }
+
+    let mut total = Duration::ZERO;
+    let mut number = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let n = number.parse::<i64>().map_err(|_| err())?;
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            'w' => 7 * 86400,
+            _ => return Err(err()),
+        };
+        total = n
+            .checked_mul(unit)
+            .and_then(|seconds| total.checked_add(Duration::seconds(seconds)))
+            .ok_or_else(|| format!("'{input}' is too long a duration"))?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        return Err(err());
+    }
+
+    Ok(total)
+}
+
+pub fn format(duration: Duration) -> String {
+    if duration.is_zero() {
+        return "0m".to_string();
+    }
+
+    let mut result = String::new();
+    let mut seconds = duration.whole_seconds();
+    for (unit, size) in [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)] {
+        if seconds >= size {
+            result.push_str(&format!("{}{unit}", seconds / size));
+            seconds %= size;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse("25m").unwrap(), Duration::minutes(25));
+        assert_eq!(parse("1h30m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse("2w").unwrap(), Duration::days(14));
+        assert!(parse("").is_err());
+        assert!(parse("25").is_err());
+        assert!(parse("m").is_err());
+        assert!(parse("5y").is_err());
+        assert_eq!(
+            parse("9999999999999999w").unwrap_err(),
+            "'9999999999999999w' is too long a duration"
+        );
+        assert!(parse("9223372036854775807s1s").is_err());
+    }
+
+    #[test]
+    fn format_durations() {
+        assert_eq!(format(Duration::minutes(90)), "1h30m");
+        assert_eq!(format(Duration::days(1)), "1d");
+        assert_eq!(format(Duration::ZERO), "0m");
+    }
+}
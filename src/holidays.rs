@@ -0,0 +1,417 @@
+//! Days jobs shouldn't run on, like public holidays or change freezes, read
+//! from a file of dates:
+//!
+//! ```text
+//! # One date or range of dates per line, optionally followed by a name.
+//! 2026-12-25 Christmas Day
+//! 2026-12-18..2027-01-04 Change freeze
+//! ```
+//!
+//! or from an iCalendar (`.ics`) file, whose events block out the days they
+//! cover.
+
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+use crate::cron::{self, Schedule};
+
+/// Consecutive days jobs shouldn't run on.
+#[derive(Debug, PartialEq)]
+pub struct Blackout {
+    pub first: Date,
+    /// The last day blocked out, which may be `first`.
+    pub last: Date,
+    pub name: Option<String>,
+    /// Whether the days are blocked out again every year from `first` on.
+    pub yearly: bool,
+}
+
+impl Blackout {
+    pub fn contains(&self, date: Date) -> bool {
+        if !self.yearly || date < self.first {
+            return self.first <= date && date <= self.last;
+        }
+        if (self.last - self.first).whole_days() >= 365 {
+            return true;
+        }
+        let day = |d: Date| (d.month() as u8, d.day());
+        let (first, last, date) = (day(self.first), day(self.last), day(date));
+        if first <= last {
+            first <= date && date <= last
+        } else {
+            // Across the end of the year.
+            first <= date || date <= last
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Holidays {
+    pub blackouts: Vec<Blackout>,
+}
+
+impl Holidays {
+    /// Reads a date file, or an iCalendar file if it starts like one. Times
+    /// in UTC are taken on the days they are at `offset`.
+    pub fn from_str(input: &str, offset: UtcOffset) -> Result<Holidays, String> {
+        if input.trim_start().starts_with("BEGIN:VCALENDAR") {
+            parse_ics(input, offset)
+        } else {
+            parse_dates(input)
+        }
+    }
+
+    pub fn extend(&mut self, other: Holidays) {
+        self.blackouts.extend(other.blackouts);
+    }
+
+    /// The first blackout the date is in, if any.
+    pub fn on(&self, date: Date) -> Option<&Blackout> {
+        self.blackouts.iter().find(|b| b.contains(date))
+    }
+}
+
+/// The runs of the schedule from `from_time` on that aren't on a blocked out
+/// day. Stops looking after `cron::MAX_SEARCH_YEARS`, in case every run is.
+pub fn effective_occurrences<'a>(
+    from_time: OffsetDateTime,
+    schedule: &'a Schedule,
+    holidays: &'a Holidays,
+) -> impl Iterator<Item = OffsetDateTime> + 'a {
+    let last_year = from_time.year() + cron::MAX_SEARCH_YEARS;
+    cron::occurrences(from_time, schedule)
+        .take_while(move |t| t.year() <= last_year)
+        .filter(|t| holidays.on(t.date()).is_none())
+}
+
+fn parse_dates(input: &str) -> Result<Holidays, String> {
+    let mut blackouts = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let err = |e: String| format!("line {}: {e}", i + 1);
+        let (dates, name) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let (first, last) = dates.split_once("..").unwrap_or((dates, dates));
+        let (first, last) = (
+            parse_date(first).map_err(err)?,
+            parse_date(last).map_err(err)?,
+        );
+        if last < first {
+            return Err(err(format!("'{dates}' ends before it starts")));
+        }
+        let name = name.trim();
+        blackouts.push(Blackout {
+            first,
+            last,
+            name: (!name.is_empty()).then(|| name.to_string()),
+            yearly: false,
+        });
+    }
+    Ok(Holidays { blackouts })
+}
+
+/// Parses `2026-12-25`, or `20261225` as in iCalendar files.
+fn parse_date(input: &str) -> Result<Date, String> {
+    let err = || format!("'{input}' is not a date (YYYY-MM-DD)");
+    let digits = input.replace('-', "");
+    if digits.len() != 8
+        || !digits.chars().all(|c| c.is_ascii_digit())
+        || (input.contains('-') && input.len() != 10)
+    {
+        return Err(err());
+    }
+    let number = |range: std::ops::Range<usize>| digits[range].parse::<u8>().map_err(|_| err());
+    let year = digits[..4].parse::<i32>().map_err(|_| err())?;
+    let month = Month::try_from(number(4..6)?).map_err(|_| err())?;
+    Date::from_calendar_date(year, month, number(6..8)?).map_err(|_| err())
+}
+
+#[derive(Default)]
+struct Event {
+    start: Option<(Date, bool)>,
+    end: Option<(Date, bool)>,
+    name: Option<String>,
+    /// The line of the RRULE and its parts.
+    repeat: Option<(usize, String)>,
+}
+
+fn parse_ics(input: &str, offset: UtcOffset) -> Result<Holidays, String> {
+    // Long lines are folded by starting the rest with a space or a tab.
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ => lines.push((i + 1, line.to_string())),
+        }
+    }
+
+    let mut blackouts = Vec::new();
+    let mut event: Option<Event> = None;
+    for (number, line) in lines {
+        let err = |e: String| format!("line {number}: {e}");
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, parameters) = name.split_once(';').unwrap_or((name, ""));
+        let name = name.to_ascii_uppercase();
+        match (name.as_str(), event.as_mut()) {
+            ("BEGIN", None) if value == "VEVENT" => event = Some(Event::default()),
+            ("END", Some(_)) if value == "VEVENT" => {
+                let e = event.take().unwrap();
+                let (first, _) = e
+                    .start
+                    .ok_or_else(|| err("event has no DTSTART".to_string()))?;
+                if let Some((line, rule)) = &e.repeat {
+                    check_yearly(rule, first).map_err(|e| format!("line {line}: {e}"))?;
+                }
+                let last = match e.end {
+                    // The end is exclusive, unless it's during a day.
+                    Some((end, true)) => end,
+                    Some((end, false)) => end.previous_day().unwrap_or(end),
+                    None => first,
+                };
+                blackouts.push(Blackout {
+                    first,
+                    last: last.max(first),
+                    name: e.name,
+                    yearly: e.repeat.is_some(),
+                });
+            }
+            ("DTSTART", Some(e)) => {
+                e.start = Some(parse_ics_date(parameters, value, offset).map_err(err)?)
+            }
+            ("DTEND", Some(e)) => {
+                e.end = Some(parse_ics_date(parameters, value, offset).map_err(err)?)
+            }
+            ("SUMMARY", Some(e)) => e.name = Some(unescape(value)),
+            ("RRULE", Some(e)) => e.repeat = Some((number, value.to_string())),
+            _ => {}
+        }
+    }
+    Ok(Holidays { blackouts })
+}
+
+/// Checks that the RRULE repeats every year on the days from `start`. Rules
+/// exported from calendars often spell out the month and day of `start`.
+fn check_yearly(rule: &str, start: Date) -> Result<(), String> {
+    let same = |part: &str| match part.split_once('=') {
+        Some(("FREQ", "YEARLY") | ("INTERVAL", "1")) => true,
+        Some(("BYMONTH", month)) => month.parse() == Ok(start.month() as u8),
+        Some(("BYMONTHDAY", day)) => day.parse() == Ok(start.day()),
+        _ => false,
+    };
+    if !rule.contains("FREQ=YEARLY") || !rule.split(';').all(same) {
+        return Err(format!(
+            "'{rule}' is not supported, only events repeating every year are"
+        ));
+    }
+    Ok(())
+}
+
+/// The day of an iCalendar date or date-time, and whether the date-time is
+/// after midnight. Times in UTC are moved to `offset` first, and times
+/// without a time zone are taken as they are.
+fn parse_ics_date(
+    parameters: &str,
+    value: &str,
+    offset: UtcOffset,
+) -> Result<(Date, bool), String> {
+    let Some((date, time)) = value.split_once('T') else {
+        return Ok((parse_date(value)?, false));
+    };
+    if parameters.to_ascii_uppercase().contains("TZID=") {
+        return Err(format!(
+            "'{value}' is in a named time zone, which isn't supported; use dates or times in UTC"
+        ));
+    }
+    let (time, utc) = match time.strip_suffix('Z') {
+        Some(time) => (time, true),
+        None => (time, false),
+    };
+    let err = || format!("'{value}' is not a date-time (YYYYMMDDTHHMMSS)");
+    let number = |range: std::ops::Range<usize>| {
+        time.get(range)
+            .and_then(|n| n.parse::<u8>().ok())
+            .ok_or_else(err)
+    };
+    if time.len() != 6 {
+        return Err(err());
+    }
+    let time = Time::from_hms(number(0..2)?, number(2..4)?, number(4..6)?).map_err(|_| err())?;
+    let datetime = PrimitiveDateTime::new(parse_date(date)?, time);
+    let datetime = match utc {
+        true => datetime.assume_utc().to_offset(offset),
+        false => datetime.assume_offset(offset),
+    };
+    Ok((datetime.date(), datetime.time() != Time::MIDNIGHT))
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => result.push(' '),
+            Some(c) => result.push(c),
+            None => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{date, datetime};
+
+    #[test]
+    fn date_files() {
+        let holidays = Holidays::from_str(
+            "# Holidays\n2026-12-25 Christmas Day\n\n2026-12-18..2027-01-04 Change freeze\n2026-05-01",
+            UtcOffset::UTC,
+        )
+        .unwrap();
+
+        assert_eq!(holidays.blackouts.len(), 3);
+        assert_eq!(
+            holidays.on(date!(2026 - 12 - 25)).unwrap().name.as_deref(),
+            Some("Christmas Day")
+        );
+        assert_eq!(
+            holidays.on(date!(2027 - 01 - 04)).unwrap().name.as_deref(),
+            Some("Change freeze")
+        );
+        assert_eq!(holidays.on(date!(2026 - 05 - 01)).unwrap().name, None);
+        assert!(holidays.on(date!(2027 - 01 - 05)).is_none());
+        assert!(holidays.on(date!(2027 - 12 - 25)).is_none());
+
+        assert_eq!(
+            Holidays::from_str("2026-12-25\n2026-13-01", UtcOffset::UTC).unwrap_err(),
+            "line 2: '2026-13-01' is not a date (YYYY-MM-DD)"
+        );
+        assert_eq!(
+            Holidays::from_str("2027-01-04..2026-12-18", UtcOffset::UTC).unwrap_err(),
+            "line 1: '2027-01-04..2026-12-18' ends before it starts"
+        );
+    }
+
+    #[test]
+    fn ics_files() {
+        let holidays = Holidays::from_str(
+            "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+DTSTART;VALUE=DATE:20261224\r
+DTEND;VALUE=DATE:20261227\r
+SUMMARY:Christmas\\, Boxing\r
+  Day\r
+RRULE:FREQ=YEARLY\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+DTSTART:20261230T220000Z\r
+DTEND:20261231T020000Z\r
+SUMMARY:Maintenance\r
+END:VEVENT\r
+END:VCALENDAR\r
+",
+            UtcOffset::UTC,
+        )
+        .unwrap();
+
+        assert_eq!(
+            holidays.blackouts[0],
+            Blackout {
+                first: date!(2026 - 12 - 24),
+                last: date!(2026 - 12 - 26),
+                name: Some("Christmas, Boxing Day".to_string()),
+                yearly: true,
+            }
+        );
+        assert!(holidays.on(date!(2030 - 12 - 26)).is_some());
+        assert!(holidays.on(date!(2030 - 12 - 27)).is_none());
+        assert!(holidays.on(date!(2025 - 12 - 25)).is_none());
+        assert_eq!(holidays.blackouts[1].first, date!(2026 - 12 - 30));
+        assert_eq!(holidays.blackouts[1].last, date!(2026 - 12 - 31));
+
+        assert_eq!(
+            Holidays::from_str(
+                "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:20260101\nRRULE:FREQ=WEEKLY\nEND:VEVENT",
+                UtcOffset::UTC
+            )
+            .unwrap_err(),
+            "line 4: 'FREQ=WEEKLY' is not supported, only events repeating every year are"
+        );
+
+        let ics = |lines: &str, offset| {
+            Holidays::from_str(&format!("BEGIN:VCALENDAR\n{lines}\nEND:VCALENDAR"), offset)
+        };
+        // 22:00 UTC is already the next day two hours east.
+        let east = ics(
+            "BEGIN:VEVENT\nDTSTART:20261230T220000Z\nDTEND:20261231T020000Z\nEND:VEVENT",
+            UtcOffset::from_hms(2, 0, 0).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(east.blackouts[0].first, date!(2026 - 12 - 31));
+        assert_eq!(east.blackouts[0].last, date!(2026 - 12 - 31));
+        assert_eq!(
+            ics(
+                "BEGIN:VEVENT\nDTSTART;TZID=Europe/Oslo:20261230T220000\nEND:VEVENT",
+                UtcOffset::UTC
+            )
+            .unwrap_err(),
+            "line 3: '20261230T220000' is in a named time zone, which isn't supported; use dates or times in UTC"
+        );
+        let christmas = ics(
+            "BEGIN:VEVENT\nRRULE:FREQ=YEARLY;BYMONTH=12;BYMONTHDAY=25\nDTSTART;VALUE=DATE:20201225\nEND:VEVENT",
+            UtcOffset::UTC,
+        )
+        .unwrap();
+        assert!(christmas.on(date!(2026 - 12 - 25)).is_some());
+        assert_eq!(
+            ics(
+                "BEGIN:VEVENT\nDTSTART;VALUE=DATE:20201126\nRRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=4TH\nEND:VEVENT",
+                UtcOffset::UTC
+            )
+            .unwrap_err(),
+            "line 4: 'FREQ=YEARLY;BYMONTH=11;BYDAY=4TH' is not supported, only events repeating every year are"
+        );
+    }
+
+    #[test]
+    fn skips_blocked_out_days() {
+        let schedule = Schedule::from_str("30 9 * * 1-5").unwrap();
+        let holidays =
+            Holidays::from_str("2026-12-21..2026-12-25 Change freeze", UtcOffset::UTC).unwrap();
+        let runs = effective_occurrences(
+            datetime!(2026-12-18 12:00:00 +0:00:00),
+            &schedule,
+            &holidays,
+        )
+        .take(2)
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            runs,
+            vec![
+                datetime!(2026-12-28 09:30:00 +0:00:00),
+                datetime!(2026-12-29 09:30:00 +0:00:00)
+            ]
+        );
+
+        let always = Holidays::from_str(
+            "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:20260101\nDTEND:20270101\nRRULE:FREQ=YEARLY\nEND:VEVENT",
+            UtcOffset::UTC,
+        )
+        .unwrap();
+        assert_eq!(
+            effective_occurrences(datetime!(2026-12-18 12:00:00 +0:00:00), &schedule, &always)
+                .next(),
+            None
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use time::ext::NumericalDuration;
+use time::{Date, OffsetDateTime, Time, UtcOffset};
+
+use crate::cron::{self, Schedule, Value};
+use crate::synthesis;
+
+pub struct Inference {
+    pub lines: Vec<Schedule>,
+    /// Timestamps the lines don't produce.
+    pub outliers: Vec<OffsetDateTime>,
+    /// Runs of the lines between the first and last timestamp that are
+    /// missing from the input.
+    pub missed: Vec<OffsetDateTime>,
+    /// How much to trust the lines, from 0 to 1.
+    pub confidence: f64,
+}
+
+/// Reads one timestamp per line, from the start of the line, so log lines
+/// work as they are. Timestamps are like `2026-03-03 09:30`,
+/// `2026-03-03T09:30:00-05:00` or Unix timestamps, and are converted to
+/// `offset`, the time zone the cron lines are for. Timestamps without an
+/// offset are already in it.
+pub fn parse_timestamps(input: &str, offset: UtcOffset) -> Result<Vec<OffsetDateTime>, String> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| {
+            parse_timestamp(line, offset)
+                .map(|timestamp| timestamp.to_offset(offset))
+                .map_err(|e| format!("line {}: {e}", i + 1))
+        })
+        .collect()
+}
+
+fn parse_timestamp(line: &str, offset: UtcOffset) -> Result<OffsetDateTime, String> {
+    let mut words = line.split_whitespace();
+    let first = words.next().unwrap_or_default();
+    let err = || format!("'{first}' is not a timestamp (e.g. 2026-03-03 09:30)");
+
+    if let Ok(seconds) = first.parse::<i64>() {
+        return OffsetDateTime::from_unix_timestamp(seconds).map_err(|_| err());
+    }
+
+    let (date, rest) = match first.split_once(['T', 't']) {
+        Some((date, rest)) => (date, rest.to_string()),
+        None => (first, words.next().unwrap_or_default().to_string()),
+    };
+    let mut numbers = date.split('-').map(|n| n.parse::<i32>().ok());
+    let (Some(Some(year)), Some(Some(month)), Some(Some(day)), None) = (
+        numbers.next(),
+        numbers.next(),
+        numbers.next(),
+        numbers.next(),
+    ) else {
+        return Err(err());
+    };
+    let month = time::Month::try_from(month as u8).map_err(|_| err())?;
+    let date = Date::from_calendar_date(year, month, day as u8).map_err(|_| err())?;
+
+    // The offset is either attached to the time or the next word.
+    let (time, zone) = match rest.find(['Z', 'z', '+', '-']) {
+        Some(i) => (&rest[..i], rest[i..].to_string()),
+        None => (rest.as_str(), words.next().unwrap_or_default().to_string()),
+    };
+    let mut numbers = time
+        .split(':')
+        .map(|n| n.split('.').next().unwrap_or_default().parse::<u8>().ok());
+    let (Some(Some(hour)), Some(Some(minute))) = (numbers.next(), numbers.next()) else {
+        return Err(err());
+    };
+    let second = numbers.next().flatten().unwrap_or(0);
+    let time = Time::from_hms(hour, minute, second).map_err(|_| err())?;
+
+    let offset = match zone.as_str() {
+        "Z" | "z" | "UTC" => UtcOffset::UTC,
+        zone if zone.starts_with(['+', '-']) => {
+            let sign = if zone.starts_with('-') { -1 } else { 1 };
+            let digits = zone[1..].replace(':', "");
+            let hours = digits.get(..2).and_then(|h| h.parse::<i8>().ok());
+            let minutes = digits.get(2..4).map_or(Some(0), |m| m.parse::<i8>().ok());
+            match (hours, minutes) {
+                (Some(hours), Some(minutes)) => {
+                    UtcOffset::from_hms(sign * hours, sign * minutes, 0).map_err(|_| err())?
+                }
+                _ => return Err(err()),
+            }
+        }
+        _ => offset,
+    };
+    Ok(date.with_time(time).assume_offset(offset))
+}
+
+/// The days a time of day runs on, as cron fields.
+#[derive(Clone, PartialEq)]
+struct Days {
+    day_of_month: Value,
+    month: Value,
+    day_of_week: Value,
+}
+
+/// Infers the cron lines that ran at `runs`.
+///
+/// Every time of day that ran at least a quarter as often as the most
+/// common one gets the days it ran on: every day, some days of the week or
+/// some days of the month, whichever matches them best. Fields that ran at
+/// every value they could within the timestamps become wildcards, since
+/// that makes no difference between the first and last timestamp.
+///
+/// The confidence is the share of timestamps the lines produce, times the
+/// share of their runs that were found, times 1 - 1/n for the n timestamps
+/// that fit, so that a handful of them never counts for much.
+pub fn infer(runs: &[OffsetDateTime]) -> Result<Inference, String> {
+    let mut runs = runs
+        .iter()
+        .map(|run| run.replace_time(Time::from_hms(run.hour(), run.minute(), 0).unwrap()))
+        .collect::<Vec<_>>();
+    runs.sort();
+    runs.dedup();
+    if runs.len() < 2 {
+        return Err("need at least two different timestamps".to_string());
+    }
+    let (first, last) = (runs[0], *runs.last().unwrap());
+    let span = dates(first.date(), last.date());
+
+    let mut dates_by_time = BTreeMap::<i32, BTreeSet<Date>>::new();
+    for run in &runs {
+        let time = run.hour() as i32 * 60 + run.minute() as i32;
+        dates_by_time.entry(time).or_default().insert(run.date());
+    }
+    let most = dates_by_time.values().map(BTreeSet::len).max().unwrap();
+
+    let mut groups: Vec<(Days, Vec<i32>)> = Vec::new();
+    for (time, dates) in &dates_by_time {
+        if dates.len() * 4 < most {
+            continue;
+        }
+        // Only the days between the first and last timestamp at that time.
+        let span = span
+            .iter()
+            .copied()
+            .filter(|date| {
+                let run = date
+                    .with_time(Time::from_hms((time / 60) as u8, (time % 60) as u8, 0).unwrap())
+                    .assume_offset(first.offset());
+                first <= run && run <= last
+            })
+            .collect::<Vec<_>>();
+        let days = days(dates, &span);
+        match groups.iter_mut().find(|(d, _)| *d == days) {
+            Some((_, times)) => times.push(*time),
+            None => groups.push((days, vec![*time])),
+        }
+    }
+    let lines = groups
+        .into_iter()
+        .flat_map(|(days, times)| {
+            synthesis::times_of_day(&times)
+                .into_iter()
+                .map(move |mut line| {
+                    line.day_of_month.value = days.day_of_month.clone();
+                    line.month.value = days.month.clone();
+                    line.day_of_week.value = days.day_of_week.clone();
+                    line
+                })
+        })
+        .collect::<Vec<_>>();
+
+    let predicted = runs_between(&lines, first, last)?;
+    let observed = runs.iter().copied().collect::<BTreeSet<_>>();
+    let outliers = observed.difference(&predicted).copied().collect::<Vec<_>>();
+    let missed = predicted.difference(&observed).copied().collect::<Vec<_>>();
+    let matched = (runs.len() - outliers.len()) as f64;
+    let confidence = if matched == 0.0 {
+        0.0
+    } else {
+        matched / runs.len() as f64 * matched / predicted.len() as f64 * (1.0 - 1.0 / matched)
+    };
+
+    Ok(Inference {
+        lines,
+        outliers,
+        missed,
+        confidence,
+    })
+}
+
+fn dates(first: Date, last: Date) -> Vec<Date> {
+    std::iter::successors(Some(first), |date| date.next_day())
+        .take_while(|date| *date <= last)
+        .collect()
+}
+
+/// Picks the fields that match the dates best among `span`.
+fn days(dates: &BTreeSet<Date>, span: &[Date]) -> Days {
+    let set = |values: &mut dyn Iterator<Item = i32>| values.collect::<BTreeSet<_>>();
+    let to_vec = |values: &BTreeSet<i32>| values.iter().copied().collect::<Vec<_>>();
+
+    let months = set(&mut dates.iter().map(|d| d.month() as i32));
+    let possible_months = set(&mut span.iter().map(|d| d.month() as i32));
+    let month = if months == possible_months {
+        Value::Wildcard
+    } else {
+        Value::from_values(&to_vec(&months), 1, 12)
+    };
+    let span = span
+        .iter()
+        .filter(|d| months.contains(&(d.month() as i32)))
+        .copied()
+        .collect::<Vec<_>>();
+
+    // Values that ran on at least half the days they could have, and could
+    // have at least `min_possible` times. Days of the month need to come
+    // round twice, or any set of days would fit a log shorter than a month.
+    let frequent = |key: &dyn Fn(&Date) -> i32, min_possible: usize| {
+        let mut counts = BTreeMap::<i32, (usize, usize)>::new();
+        for date in &span {
+            counts.entry(key(date)).or_default().1 += 1;
+        }
+        for date in dates {
+            counts.entry(key(date)).or_default().0 += 1;
+        }
+        counts
+            .into_iter()
+            .filter(|(_, (ran, possible))| *possible >= min_possible && ran * 2 >= *possible)
+            .map(|(value, _)| value)
+            .collect::<BTreeSet<_>>()
+    };
+    let weekday = |d: &Date| d.weekday().number_days_from_sunday() as i32;
+    let day = |d: &Date| d.day() as i32;
+    let weekdays = frequent(&weekday, 1);
+    let days = frequent(&day, 2);
+
+    let mismatches = |predicted: &dyn Fn(&Date) -> bool| {
+        span.iter()
+            .filter(|d| predicted(d) != dates.contains(d))
+            .count()
+    };
+    let candidates = [
+        (mismatches(&|_| true), Value::Wildcard, Value::Wildcard),
+        (
+            mismatches(&|d| weekdays.contains(&weekday(d))),
+            Value::Wildcard,
+            Value::from_values(&to_vec(&weekdays), 0, 6),
+        ),
+        (
+            mismatches(&|d| days.contains(&day(d))),
+            Value::from_values(&to_vec(&days), 1, 31),
+            Value::Wildcard,
+        ),
+    ];
+    // The first, simplest one on a tie.
+    let (_, day_of_month, day_of_week) = candidates
+        .into_iter()
+        .filter(|(_, day_of_month, day_of_week)| {
+            *day_of_month != Value::List(Vec::new()) && *day_of_week != Value::List(Vec::new())
+        })
+        .min_by_key(|(mismatches, ..)| *mismatches)
+        .unwrap();
+    Days {
+        day_of_month,
+        month,
+        day_of_week,
+    }
+}
+
+/// The runs of the lines from `first` through `last`.
+fn runs_between(
+    lines: &[Schedule],
+    first: OffsetDateTime,
+    last: OffsetDateTime,
+) -> Result<BTreeSet<OffsetDateTime>, String> {
+    let mut runs = BTreeSet::new();
+    let mut next = first;
+    loop {
+        next = lines
+            .iter()
+            .map(|line| cron::next_occurrence(next, line))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .min()
+            .unwrap();
+        if next > last {
+            return Ok(runs);
+        }
+        runs.insert(next);
+        next = next.saturating_add(1.seconds());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{datetime, offset};
+
+    fn lines(inference: &Inference) -> Vec<String> {
+        inference.lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    /// Runs of the schedule from 2026-03-02, a Monday.
+    fn runs_of(schedule: &str, count: usize) -> Vec<OffsetDateTime> {
+        cron::occurrences(
+            datetime!(2026-03-02 00:00:00 UTC),
+            &Schedule::from_str(schedule).unwrap(),
+        )
+        .take(count)
+        .collect()
+    }
+
+    #[test]
+    fn reads_timestamps() {
+        let input = "\
+2026-03-03 09:30
+2026-03-03T09:30:00-05:00 backup done
+
+# comment
+2026-03-03 09:30:59.5 +01:00
+1772530200";
+        assert_eq!(
+            parse_timestamps(input, offset!(UTC)).unwrap(),
+            vec![
+                datetime!(2026-03-03 09:30:00 UTC),
+                datetime!(2026-03-03 14:30:00 UTC),
+                datetime!(2026-03-03 08:30:59 UTC),
+                datetime!(2026-03-03 09:30:00 UTC),
+            ]
+        );
+        assert_eq!(
+            parse_timestamps("2026-03-03 09:30\nyesterday", offset!(UTC)).unwrap_err(),
+            "line 2: 'yesterday' is not a timestamp (e.g. 2026-03-03 09:30)"
+        );
+    }
+
+    #[test]
+    fn infers_exact_schedules() {
+        for (schedule, count) in [
+            ("30 9 * * 1-5", 40),
+            ("*/15 * * * *", 500),
+            ("0 2 1 * *", 6),
+            ("0 9 * * 1", 10),
+            ("0 */3 * * *", 100),
+        ] {
+            let result = infer(&runs_of(schedule, count)).unwrap();
+            assert_eq!(lines(&result), vec![schedule], "from runs of {schedule}");
+            assert!(result.outliers.is_empty());
+            assert!(result.missed.is_empty());
+        }
+
+        let result = infer(&runs_of("0 0-21/3 * * *", 80)).unwrap();
+        assert!(result.confidence > 0.95);
+
+        let mut runs = runs_of("0 */3 * * *", 80);
+        runs.extend(runs_of("30 1-22/3 * * *", 80));
+        let result = infer(&runs).unwrap();
+        assert_eq!(lines(&result), vec!["0 */3 * * *", "30 1/3 * * *"]);
+    }
+
+    #[test]
+    fn reports_what_does_not_fit() {
+        let mut runs = runs_of("30 9 * * 1-5", 40);
+        let manual = datetime!(2026-03-14 16:02:00 UTC);
+        runs.push(manual);
+        runs.remove(10);
+
+        let result = infer(&runs).unwrap();
+        assert_eq!(lines(&result), vec!["30 9 * * 1-5"]);
+        assert_eq!(result.outliers, vec![manual]);
+        assert_eq!(result.missed, vec![datetime!(2026-03-16 09:30:00 UTC)]);
+        assert!(result.confidence < 0.96);
+
+        assert!(infer(&runs_of("0 9 * * *", 1)).is_err());
+        let two = infer(&runs_of("0 9 * * *", 2)).unwrap();
+        assert!(two.confidence <= 0.5);
+    }
+}
@@ -0,0 +1,559 @@
+use std::fmt::{self, Write};
+
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::check::{self, Diagnostic};
+use crate::cron::{self, DescribeOptions, Schedule, Value};
+use crate::language::Field;
+use crate::lint::{self, Lint};
+
+/// Version of the `--output json` schema described in the README. Bumped
+/// only when existing keys change meaning or go away.
+pub const SCHEMA_VERSION: i64 = 1;
+
+/// A JSON value. Objects keep their keys in the order they were added.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object(pairs: Vec<(&str, Json)>) -> Json {
+        Json::Object(
+            pairs
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    pub fn string(s: impl Into<String>) -> Json {
+        Json::String(s.into())
+    }
+
+    pub fn numbers(numbers: &[i32]) -> Json {
+        Json::Array(numbers.iter().map(|n| Json::Number(*n as i64)).collect())
+    }
+
+    pub fn optional_number(number: Option<i32>) -> Json {
+        number.map_or(Json::Null, |n| Json::Number(n as i64))
+    }
+
+    fn is_scalar(&self) -> bool {
+        !matches!(self, Json::Array(_) | Json::Object(_))
+    }
+
+    fn write(&self, out: &mut fmt::Formatter, indent: usize) -> fmt::Result {
+        let pad = "  ".repeat(indent + 1);
+        match self {
+            Json::Null => out.write_str("null"),
+            Json::Number(n) => write!(out, "{n}"),
+            Json::String(s) => write_string(out, s),
+            Json::Array(items) if items.is_empty() => out.write_str("[]"),
+            // Lists of numbers and strings fit on one line.
+            Json::Array(items) if items.iter().all(Json::is_scalar) => {
+                out.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.write_str(", ")?;
+                    }
+                    item.write(out, indent)?;
+                }
+                out.write_char(']')
+            }
+            Json::Array(items) => {
+                out.write_str("[\n")?;
+                for (i, item) in items.iter().enumerate() {
+                    out.write_str(&pad)?;
+                    item.write(out, indent + 1)?;
+                    out.write_str(if i + 1 < items.len() { ",\n" } else { "\n" })?;
+                }
+                write!(out, "{}]", "  ".repeat(indent))
+            }
+            Json::Object(pairs) if pairs.is_empty() => out.write_str("{}"),
+            Json::Object(pairs) => {
+                out.write_str("{\n")?;
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    out.write_str(&pad)?;
+                    write_string(out, key)?;
+                    out.write_str(": ")?;
+                    value.write(out, indent + 1)?;
+                    out.write_str(if i + 1 < pairs.len() { ",\n" } else { "\n" })?;
+                }
+                write!(out, "{}}}", "  ".repeat(indent))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.write(fmt, 0)
+    }
+}
+
+fn write_string(out: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+pub fn rfc3339(datetime: OffsetDateTime) -> Json {
+    Json::String(datetime.format(&Rfc3339).unwrap_or_default())
+}
+
+/// A parsed field: its kind, the numbers written in the schedule and the
+/// sorted numbers it selects.
+fn value(value: &Value, min: i32, max: i32) -> Json {
+    let mut pairs = match value {
+        Value::Step(start, step) => vec![
+            ("kind", Json::string("step")),
+            ("start", Json::optional_number(*start)),
+            ("step", Json::Number(*step as i64)),
+        ],
+        Value::Range(start, stop, step) => vec![
+            ("kind", Json::string("range")),
+            ("start", Json::Number(*start as i64)),
+            ("stop", Json::Number(*stop as i64)),
+            ("step", Json::optional_number(*step)),
+        ],
+        Value::List(list) => vec![
+            ("kind", Json::string("list")),
+            ("items", Json::numbers(list)),
+        ],
+        Value::Single(single) => vec![
+            ("kind", Json::string("single")),
+            ("value", Json::Number(*single as i64)),
+        ],
+        Value::Wildcard => vec![("kind", Json::string("wildcard"))],
+    };
+    let mut values = value.values(min, max);
+    if max == 6 {
+        // Sunday is both 0 and 7 (SUN).
+        values = values.into_iter().map(|day| day % 7).collect();
+        values.sort();
+        values.dedup();
+    }
+    pairs.insert(1, ("text", Json::string(value.to_string())));
+    pairs.push(("values", Json::numbers(&values)));
+    Json::object(pairs)
+}
+
+/// Everything `explaincron` knows about a schedule, in the format documented
+/// under "JSON output" in the README.
+pub fn explanation(
+    schedule: &Schedule,
+    options: &DescribeOptions,
+    occurrences: &[OffsetDateTime],
+) -> Json {
+    let fragments = cron::fragments(schedule, options);
+    Json::object(vec![
+        ("version", Json::Number(SCHEMA_VERSION)),
+        ("schedule", Json::string(schedule.to_string())),
+        (
+            "fields",
+            Json::object(vec![
+                (Field::Minute.name(), value(&schedule.minute.value, 0, 59)),
+                (Field::Hour.name(), value(&schedule.hour.value, 0, 23)),
+                (
+                    Field::DayOfMonth.name(),
+                    value(&schedule.day_of_month.value, 1, 31),
+                ),
+                (Field::Month.name(), value(&schedule.month.value, 1, 12)),
+                (
+                    Field::DayOfWeek.name(),
+                    value(&schedule.day_of_week.value, 0, 6),
+                ),
+            ]),
+        ),
+        (
+            "fragments",
+            Json::Array(
+                fragments
+                    .iter()
+                    .map(|fragment| {
+                        Json::object(vec![
+                            (
+                                "fields",
+                                Json::Array(
+                                    fragment
+                                        .fields
+                                        .iter()
+                                        .map(|field| Json::string(field.name()))
+                                        .collect(),
+                                ),
+                            ),
+                            ("text", Json::string(&fragment.text)),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        ("description", Json::string(cron::sentence(&fragments))),
+        (
+            "warnings",
+            Json::Array(
+                cron::warnings(schedule)
+                    .into_iter()
+                    .map(Json::String)
+                    .collect(),
+            ),
+        ),
+        (
+            "occurrences",
+            Json::Array(occurrences.iter().map(|o| rfc3339(*o)).collect()),
+        ),
+    ])
+}
+
+/// The findings of `explaincron lint`, each with the fields it's about.
+pub fn lints(schedule: &Schedule, lints: &[Lint]) -> Json {
+    Json::object(vec![
+        ("version", Json::Number(SCHEMA_VERSION)),
+        ("schedule", Json::string(schedule.to_string())),
+        (
+            "lints",
+            Json::Array(
+                lints
+                    .iter()
+                    .map(|lint| {
+                        Json::object(vec![
+                            ("rule", Json::string(lint.rule)),
+                            ("severity", Json::string(lint.severity.name())),
+                            (
+                                "fields",
+                                Json::Array(
+                                    lint.fields
+                                        .iter()
+                                        .map(|field| Json::string(field.name()))
+                                        .collect(),
+                                ),
+                            ),
+                            ("message", Json::string(&lint.message)),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The diagnostics of `explaincron check` as a SARIF 2.1.0 log, for code
+/// scanning tools. Every rule is listed, so results refer to them by index.
+pub fn sarif(diagnostics: &[Diagnostic]) -> Json {
+    let rules = check::RULES
+        .iter()
+        .copied()
+        .chain(lint::RULES.iter().map(|r| (r.id, r.severity, r.summary)))
+        .collect::<Vec<_>>();
+    let rule = |id, severity: lint::Severity, summary| {
+        Json::object(vec![
+            ("id", Json::string(id)),
+            (
+                "shortDescription",
+                Json::object(vec![("text", Json::string(summary))]),
+            ),
+            (
+                "defaultConfiguration",
+                Json::object(vec![("level", Json::string(severity.name()))]),
+            ),
+        ])
+    };
+    let result = |diagnostic: &Diagnostic| {
+        let mut location = vec![(
+            "artifactLocation",
+            Json::object(vec![("uri", Json::string(uri(&diagnostic.path)))]),
+        )];
+        if let Some((line, columns)) = &diagnostic.location {
+            location.push((
+                "region",
+                Json::object(vec![
+                    ("startLine", Json::Number(*line as i64)),
+                    ("startColumn", Json::Number(columns.start as i64)),
+                    ("endColumn", Json::Number(columns.end as i64)),
+                ]),
+            ));
+        }
+        let message = match &diagnostic.explanation {
+            Some(explanation) => format!("{}\n\n{explanation}", diagnostic.message),
+            None => diagnostic.message.clone(),
+        };
+        let mut result = vec![("ruleId", Json::string(diagnostic.rule))];
+        // Every rule should be listed, but a wrong index is worse than none.
+        if let Some(index) = rules.iter().position(|r| r.0 == diagnostic.rule) {
+            result.push(("ruleIndex", Json::Number(index as i64)));
+        }
+        result.extend([
+            ("level", Json::string(diagnostic.severity.name())),
+            (
+                "message",
+                Json::object(vec![("text", Json::string(message))]),
+            ),
+            (
+                "locations",
+                Json::Array(vec![Json::object(vec![(
+                    "physicalLocation",
+                    Json::object(location),
+                )])]),
+            ),
+        ]);
+        Json::object(result)
+    };
+
+    Json::object(vec![
+        ("$schema", Json::string(SARIF_SCHEMA)),
+        ("version", Json::string("2.1.0")),
+        (
+            "runs",
+            Json::Array(vec![Json::object(vec![
+                (
+                    "tool",
+                    Json::object(vec![(
+                        "driver",
+                        Json::object(vec![
+                            ("name", Json::string(env!("CARGO_PKG_NAME"))),
+                            ("version", Json::string(env!("CARGO_PKG_VERSION"))),
+                            ("informationUri", Json::string(env!("CARGO_PKG_HOMEPAGE"))),
+                            (
+                                "rules",
+                                Json::Array(
+                                    rules
+                                        .iter()
+                                        .map(|(id, severity, summary)| {
+                                            rule(*id, *severity, *summary)
+                                        })
+                                        .collect(),
+                                ),
+                            ),
+                        ]),
+                    )]),
+                ),
+                (
+                    "results",
+                    Json::Array(diagnostics.iter().map(result).collect()),
+                ),
+            ])]),
+        ),
+    ])
+}
+
+/// A URI for a path: a `file://` URI if it's absolute, otherwise relative,
+/// with the characters that mean something else in URIs escaped.
+fn uri(path: &str) -> String {
+    let escaped = path
+        .chars()
+        .map(|c| match c {
+            '%' | ' ' | '#' | '?' => format!("%{:02X}", c as u32),
+            c => c.to_string(),
+        })
+        .collect::<String>();
+    if path.starts_with('/') {
+        format!("file://{escaped}")
+    } else {
+        escaped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(
+            Json::string("say \"hi\"\\\n\u{1}").to_string(),
+            r#""say \"hi\"\\\n\u0001""#
+        );
+    }
+
+    #[test]
+    fn explains_schedule() {
+        let schedule = Schedule::from_str("30 9 * * 1-5").unwrap();
+        let json = explanation(
+            &schedule,
+            &DescribeOptions::default(),
+            &[datetime!(2026-03-03 09:30:00 -5:00)],
+        );
+
+        assert_eq!(
+            json.to_string(),
+            r#"{
+  "version": 1,
+  "schedule": "30 9 * * 1-5",
+  "fields": {
+    "minute": {
+      "kind": "single",
+      "text": "30",
+      "value": 30,
+      "values": [30]
+    },
+    "hour": {
+      "kind": "single",
+      "text": "9",
+      "value": 9,
+      "values": [9]
+    },
+    "day_of_month": {
+      "kind": "wildcard",
+      "text": "*",
+      "values": [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31]
+    },
+    "month": {
+      "kind": "wildcard",
+      "text": "*",
+      "values": [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+    },
+    "day_of_week": {
+      "kind": "range",
+      "text": "1-5",
+      "start": 1,
+      "stop": 5,
+      "step": null,
+      "values": [1, 2, 3, 4, 5]
+    }
+  },
+  "fragments": [
+    {
+      "fields": ["minute", "hour"],
+      "text": "at 09:30"
+    },
+    {
+      "fields": ["day_of_week"],
+      "text": "on weekdays"
+    }
+  ],
+  "description": "At 09:30 on weekdays.",
+  "warnings": [],
+  "occurrences": ["2026-03-03T09:30:00-05:00"]
+}"#
+        );
+    }
+
+    #[test]
+    fn schedule_reparses() {
+        for (input, written) in [
+            ("0 0 * * SUN", "0 0 * * 7"),
+            ("0 0 * * MON-SUN", "0 0 * * 1-7"),
+        ] {
+            let schedule = Schedule::from_str(input).unwrap();
+            let json = explanation(&schedule, &DescribeOptions::default(), &[]).to_string();
+
+            assert!(json.contains(&format!("\n  \"schedule\": \"{written}\",\n")));
+            assert_eq!(Schedule::from_str(written).unwrap(), schedule);
+        }
+    }
+
+    #[test]
+    fn lists_lints() {
+        let schedule = Schedule::from_str("* 2 * * *").unwrap();
+        let found = crate::lint::Linter::default().lint(&schedule);
+
+        assert_eq!(
+            lints(&schedule, &found).to_string(),
+            r#"{
+  "version": 1,
+  "schedule": "* 2 * * *",
+  "lints": [
+    {
+      "rule": "every-minute",
+      "severity": "warning",
+      "fields": ["minute", "hour"],
+      "message": "runs every minute from 02:00 to 02:59; write '0 2 * * *' to run once at 02:00"
+    }
+  ]
+}"#
+        );
+    }
+
+    #[test]
+    fn sarif_log() {
+        let diagnostics = check::check(
+            "cron.d/my jobs",
+            "* 2 * * * poll\n61 * * * * x",
+            &check::Checks {
+                linter: Some(lint::Linter::default()),
+                policy: None,
+                from_time: datetime!(2026-10-17 12:00:00 +0:00:00),
+                format: Default::default(),
+            },
+        );
+        let log = sarif(&diagnostics).to_string();
+
+        assert!(log.starts_with(
+            r#"{
+  "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+  "version": "2.1.0","#
+        ));
+        assert!(log.contains(
+            r#"{
+              "id": "every-minute",
+              "shortDescription": {
+                "text": "runs every minute of some hours, like '* 2 * * *'"
+              },
+              "defaultConfiguration": {
+                "level": "warning"
+              }
+            }"#
+        ));
+        assert!(log.contains(
+            r#"{
+          "ruleId": "every-minute",
+          "ruleIndex": 4,
+          "level": "warning",
+          "message": {
+            "text": "runs every minute from 02:00 to 02:59; write '0 2 * * *' to run once at 02:00\n\n'* 2 * * *': At every minute past hour 2."
+          },
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "cron.d/my%20jobs"
+                },
+                "region": {
+                  "startLine": 1,
+                  "startColumn": 1,
+                  "endColumn": 4
+                }
+              }
+            }
+          ]
+        }"#
+        ));
+        assert!(log.contains(
+            r#""ruleId": "invalid-entry",
+          "ruleIndex": 0,
+          "level": "error","#
+        ));
+        let unlisted = sarif(&[Diagnostic {
+            path: "crontab".to_string(),
+            location: None,
+            severity: lint::Severity::Note,
+            rule: "unlisted",
+            message: "x".to_string(),
+            explanation: None,
+        }])
+        .to_string();
+        assert!(unlisted.contains(
+            r#""ruleId": "unlisted",
+          "level": "note","#
+        ));
+    }
+}
@@ -0,0 +1,626 @@
+use crate::cron::{join_oxford, MONTH_NAMES, WEEK_DAY_NAMES};
+
+/// Languages schedules can be described in. Month and weekday names of the
+/// language are also accepted in schedules.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+    Norwegian,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Minute,
+    Hour,
+    DayOfMonth,
+    Month,
+    DayOfWeek,
+}
+
+impl Field {
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Minute => "minute",
+            Field::Hour => "hour",
+            Field::DayOfMonth => "day_of_month",
+            Field::Month => "month",
+            Field::DayOfWeek => "day_of_week",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayGroup {
+    Weekdays,
+    Weekends,
+}
+
+impl Language {
+    pub fn from_str(s: &str) -> Result<Language, String> {
+        match s.to_lowercase().as_str() {
+            "en" => Ok(Language::English),
+            "de" => Ok(Language::German),
+            "fr" => Ok(Language::French),
+            "es" => Ok(Language::Spanish),
+            "no" | "nb" | "nn" => Ok(Language::Norwegian),
+            _ => Err(format!(
+                "'{s}' is not a supported language (en, de, fr, es or no)"
+            )),
+        }
+    }
+
+    /// The language of LC_ALL, LC_MESSAGES or LANG, e.g. German for
+    /// `de_AT.UTF-8`. English if that language isn't supported.
+    pub fn from_env() -> Language {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+            .unwrap_or_default();
+        Language::from_locale(&locale)
+    }
+
+    fn from_locale(locale: &str) -> Language {
+        let code = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default();
+        Language::from_str(code).unwrap_or_default()
+    }
+
+    fn months(self) -> [&'static str; 12] {
+        match self {
+            Language::English => [
+                "January",
+                "February",
+                "March",
+                "April",
+                "May",
+                "June",
+                "July",
+                "August",
+                "September",
+                "October",
+                "November",
+                "December",
+            ],
+            Language::German => [
+                "Januar",
+                "Februar",
+                "März",
+                "April",
+                "Mai",
+                "Juni",
+                "Juli",
+                "August",
+                "September",
+                "Oktober",
+                "November",
+                "Dezember",
+            ],
+            Language::French => [
+                "janvier",
+                "février",
+                "mars",
+                "avril",
+                "mai",
+                "juin",
+                "juillet",
+                "août",
+                "septembre",
+                "octobre",
+                "novembre",
+                "décembre",
+            ],
+            Language::Spanish => [
+                "enero",
+                "febrero",
+                "marzo",
+                "abril",
+                "mayo",
+                "junio",
+                "julio",
+                "agosto",
+                "septiembre",
+                "octubre",
+                "noviembre",
+                "diciembre",
+            ],
+            Language::Norwegian => [
+                "januar",
+                "februar",
+                "mars",
+                "april",
+                "mai",
+                "juni",
+                "juli",
+                "august",
+                "september",
+                "oktober",
+                "november",
+                "desember",
+            ],
+        }
+    }
+
+    /// Weekday names from Monday through Sunday.
+    fn weekdays(self) -> [&'static str; 7] {
+        match self {
+            Language::English => [
+                "Monday",
+                "Tuesday",
+                "Wednesday",
+                "Thursday",
+                "Friday",
+                "Saturday",
+                "Sunday",
+            ],
+            Language::German => [
+                "Montag",
+                "Dienstag",
+                "Mittwoch",
+                "Donnerstag",
+                "Freitag",
+                "Samstag",
+                "Sonntag",
+            ],
+            Language::French => [
+                "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+            ],
+            Language::Spanish => [
+                "lunes",
+                "martes",
+                "miércoles",
+                "jueves",
+                "viernes",
+                "sábado",
+                "domingo",
+            ],
+            Language::Norwegian => [
+                "mandag", "tirsdag", "onsdag", "torsdag", "fredag", "lørdag", "søndag",
+            ],
+        }
+    }
+
+    pub fn month(self, i: i32) -> &'static str {
+        self.months()[(i - 1).clamp(0, 11) as usize]
+    }
+
+    /// The name of a day of the week, where Sunday is 0 or 7.
+    pub fn weekday(self, i: i32) -> &'static str {
+        self.weekdays()[(i + 6).rem_euclid(7) as usize]
+    }
+
+    /// Weekday names as used for recurring days ("los lunes", "på mandager").
+    fn weekday_plural(self, i: i32) -> String {
+        let name = self.weekday(i);
+        match self {
+            Language::Spanish if name.ends_with('o') => format!("{name}s"),
+            Language::Norwegian => format!("{name}er"),
+            _ => name.to_string(),
+        }
+    }
+
+    /// The word joining the last two items of a list.
+    fn and(self) -> &'static str {
+        match self {
+            Language::English => "and",
+            Language::German => "und",
+            Language::French => "et",
+            Language::Spanish => "y",
+            Language::Norwegian => "og",
+        }
+    }
+
+    /// Joins items like "1, 2, and 3" in English and "1, 2 und 3" in German.
+    pub fn join(self, items: &[String]) -> String {
+        if self == Language::English {
+            return join_oxford(items, |item| item.clone());
+        }
+        match items.split_last() {
+            None => String::new(),
+            Some((last, [])) => last.clone(),
+            Some((last, first)) => format!("{} {} {last}", first.join(", "), self.and()),
+        }
+    }
+
+    /// The ordinal of a step, like "3rd " or "3. ". Empty for every single
+    /// one, since "every minute" reads better than "every 1st minute".
+    fn step_ordinal(self, step: i32) -> String {
+        if step == 1 {
+            return String::new();
+        }
+        match self {
+            Language::English => {
+                let suffix = match (step % 10, step % 100) {
+                    (1, n) if n != 11 => "st",
+                    (2, n) if n != 12 => "nd",
+                    (3, n) if n != 13 => "rd",
+                    _ => "th",
+                };
+                format!("{step}{suffix} ")
+            }
+            _ => format!("{step}. "),
+        }
+    }
+
+    /// "every 3rd minute", "jede 3. Minute", "toutes les 3 minutes" and so on,
+    /// with the preposition the field needs.
+    pub fn every(self, field: Field, step: Option<i32>) -> String {
+        let step = step.unwrap_or(1);
+        let nth = self.step_ordinal(step);
+        match self {
+            Language::English => {
+                let (preposition, noun) = match field {
+                    Field::Minute => ("at", "minute"),
+                    Field::Hour => ("past", "hour"),
+                    Field::DayOfMonth => ("on", "day-of-month"),
+                    Field::Month => ("in", "month"),
+                    Field::DayOfWeek => ("on", "day-of-week"),
+                };
+                format!("{preposition} every {nth}{noun}")
+            }
+            Language::German => match field {
+                Field::Minute => format!("in jeder {nth}Minute"),
+                Field::Hour => format!("in jeder {nth}Stunde"),
+                Field::DayOfMonth => format!("an jedem {nth}Tag des Monats"),
+                Field::Month => format!("in jedem {nth}Monat"),
+                Field::DayOfWeek => format!("an jedem {nth}Wochentag"),
+            },
+            Language::French => {
+                let (all, noun, nouns) = match field {
+                    Field::Minute => ("toutes les", "minutes", "minutes"),
+                    Field::Hour => ("toutes les", "heures", "heures"),
+                    Field::DayOfMonth => ("tous les", "jours", "jours"),
+                    Field::Month => ("tous les", "mois", "mois"),
+                    Field::DayOfWeek => ("tous les", "jours de la semaine", "jours de la semaine"),
+                };
+                if step == 1 {
+                    format!("{all} {noun}")
+                } else {
+                    format!("{all} {step} {nouns}")
+                }
+            }
+            Language::Spanish => {
+                let (noun, nouns) = match field {
+                    Field::Minute => ("minuto", "minutos"),
+                    Field::Hour => ("hora", "horas"),
+                    Field::DayOfMonth => ("día del mes", "días del mes"),
+                    Field::Month => ("mes", "meses"),
+                    Field::DayOfWeek => ("día de la semana", "días de la semana"),
+                };
+                if step == 1 {
+                    format!("cada {noun}")
+                } else {
+                    format!("cada {step} {nouns}")
+                }
+            }
+            Language::Norwegian => match field {
+                Field::Minute => format!("hvert {nth}minutt"),
+                Field::Hour => format!("hver {nth}time"),
+                Field::DayOfMonth => format!("hver {nth}dag i måneden"),
+                Field::Month => format!("hver {nth}måned"),
+                Field::DayOfWeek => format!("hver {nth}ukedag"),
+            },
+        }
+    }
+
+    fn value_name(self, field: Field, i: i32) -> String {
+        match field {
+            Field::Month => self.month(i).to_string(),
+            Field::DayOfWeek => self.weekday(i).to_string(),
+            _ => i.to_string(),
+        }
+    }
+
+    /// " from 2 through 59", " von März bis Dezember" and so on.
+    pub fn range(self, field: Field, start: i32, stop: i32) -> String {
+        let start = self.value_name(field, start);
+        let stop = self.value_name(field, stop);
+        match self {
+            Language::English => format!(" from {start} through {stop}"),
+            Language::German => format!(" von {start} bis {stop}"),
+            Language::French if starts_with_vowel(&start) => format!(" d'{start} à {stop}"),
+            Language::French => format!(" de {start} à {stop}"),
+            Language::Spanish => format!(" de {start} a {stop}"),
+            Language::Norwegian => format!(" fra {start} til {stop}"),
+        }
+    }
+
+    /// "at minute 5", "on Monday and Friday", "le 1er du mois" and so on.
+    pub fn at(self, field: Field, values: &[i32]) -> String {
+        let several = values.len() > 1;
+        let list = |name: &dyn Fn(i32) -> String| {
+            self.join(&values.iter().map(|i| name(*i)).collect::<Vec<_>>())
+        };
+        let numbers = list(&|i| i.to_string());
+        match (self, field) {
+            (Language::English, Field::Minute) => format!("at minute {numbers}"),
+            (Language::English, Field::Hour) => format!("past hour {numbers}"),
+            (Language::English, Field::DayOfMonth) => format!("on day-of-month {numbers}"),
+            (Language::English, Field::Month) => {
+                format!("in {}", list(&|i| self.value_name(field, i)))
+            }
+            (Language::English, Field::DayOfWeek) => {
+                format!("on {}", list(&|i| self.value_name(field, i)))
+            }
+
+            (Language::German, Field::Minute) if several => format!("in den Minuten {numbers}"),
+            (Language::German, Field::Minute) => format!("in Minute {numbers}"),
+            (Language::German, Field::Hour) if several => format!("in den Stunden {numbers}"),
+            (Language::German, Field::Hour) => format!("in Stunde {numbers}"),
+            (Language::German, Field::DayOfMonth) => {
+                format!("am {} Tag des Monats", list(&|i| format!("{i}.")))
+            }
+            (Language::German, Field::Month) => {
+                format!("im {}", list(&|i| self.value_name(field, i)))
+            }
+            (Language::German, Field::DayOfWeek) => {
+                format!("am {}", list(&|i| self.value_name(field, i)))
+            }
+
+            (Language::French, Field::Minute) if several => format!("aux minutes {numbers}"),
+            (Language::French, Field::Minute) => format!("à la minute {numbers}"),
+            (Language::French, Field::Hour) if several => format!("pendant les heures {numbers}"),
+            (Language::French, Field::Hour) => format!("pendant l'heure {numbers}"),
+            (Language::French, Field::DayOfMonth) => format!(
+                "le {} du mois",
+                list(&|i| if i == 1 {
+                    "1er".to_string()
+                } else {
+                    i.to_string()
+                })
+            ),
+            (Language::French, Field::Month) => {
+                format!("en {}", list(&|i| self.value_name(field, i)))
+            }
+            (Language::French, Field::DayOfWeek) => {
+                format!("le {}", list(&|i| self.value_name(field, i)))
+            }
+
+            (Language::Spanish, Field::Minute) if several => format!("en los minutos {numbers}"),
+            (Language::Spanish, Field::Minute) => format!("en el minuto {numbers}"),
+            (Language::Spanish, Field::Hour) if several => format!("durante las horas {numbers}"),
+            (Language::Spanish, Field::Hour) => format!("durante la hora {numbers}"),
+            (Language::Spanish, Field::DayOfMonth) if several => {
+                format!("los días {numbers} del mes")
+            }
+            (Language::Spanish, Field::DayOfMonth) => format!("el día {numbers} del mes"),
+            (Language::Spanish, Field::Month) => {
+                format!("en {}", list(&|i| self.value_name(field, i)))
+            }
+            (Language::Spanish, Field::DayOfWeek) => {
+                format!("los {}", list(&|i| self.weekday_plural(i)))
+            }
+
+            (Language::Norwegian, Field::Minute) => format!("i minutt {numbers}"),
+            (Language::Norwegian, Field::Hour) => format!("i time {numbers}"),
+            (Language::Norwegian, Field::DayOfMonth) => {
+                format!("den {} i måneden", list(&|i| format!("{i}.")))
+            }
+            (Language::Norwegian, Field::Month) => {
+                format!("i {}", list(&|i| self.value_name(field, i)))
+            }
+            (Language::Norwegian, Field::DayOfWeek) => {
+                format!("på {}", list(&|i| self.weekday_plural(i)))
+            }
+        }
+    }
+
+    /// "at 09:30 and 17:00" and so on.
+    pub fn at_times(self, times: &[String]) -> String {
+        let list = self.join(times);
+        match self {
+            Language::English => format!("at {list}"),
+            Language::German => format!("um {list}"),
+            Language::French => format!("à {list}"),
+            // One o'clock is singular: "a la 1:30 PM".
+            Language::Spanish
+                if times.len() == 1 && list.trim_start_matches('0').starts_with("1:") =>
+            {
+                format!("a la {list}")
+            }
+            Language::Spanish => format!("a las {list}"),
+            Language::Norwegian => format!("klokken {list}"),
+        }
+    }
+
+    pub fn day_group(self, group: DayGroup) -> &'static str {
+        match (self, group) {
+            (Language::English, DayGroup::Weekdays) => "on weekdays",
+            (Language::English, DayGroup::Weekends) => "on weekends",
+            (Language::German, DayGroup::Weekdays) => "an Werktagen",
+            (Language::German, DayGroup::Weekends) => "am Wochenende",
+            (Language::French, DayGroup::Weekdays) => "en semaine",
+            (Language::French, DayGroup::Weekends) => "le week-end",
+            (Language::Spanish, DayGroup::Weekdays) => "entre semana",
+            (Language::Spanish, DayGroup::Weekends) => "los fines de semana",
+            (Language::Norwegian, DayGroup::Weekdays) => "på hverdager",
+            (Language::Norwegian, DayGroup::Weekends) => "i helgene",
+        }
+    }
+
+    /// What joins the day-of-week to the day-of-month. Cron runs on either
+    /// day, unless the day-of-month starts with `*`, in which case the
+    /// day-of-week narrows it down.
+    pub fn day_of_week_connector(self, narrows: bool) -> &'static str {
+        match (self, narrows) {
+            (Language::English, false) => "and ",
+            (Language::English, true) => "if it's ",
+            (Language::German, false) => "und ",
+            (Language::German, true) => "und nur ",
+            (Language::French, false) => "et ",
+            (Language::French, true) => "mais seulement ",
+            (Language::Spanish, false) => "y ",
+            (Language::Spanish, true) => "pero solo ",
+            (Language::Norwegian, false) => "og ",
+            (Language::Norwegian, true) => "men bare ",
+        }
+    }
+
+    /// What joins the description of a schedule to one whose times it
+    /// leaves out ("except") or is narrowed down to ("but only").
+    pub fn set_connector(self, except: bool) -> &'static str {
+        match (self, except) {
+            (Language::English, true) => ", except ",
+            (Language::English, false) => ", but only ",
+            (Language::German, true) => ", außer ",
+            (Language::German, false) => ", aber nur ",
+            (Language::French, true) => ", sauf ",
+            (Language::French, false) => ", mais seulement ",
+            (Language::Spanish, true) => ", excepto ",
+            (Language::Spanish, false) => ", pero solo ",
+            (Language::Norwegian, true) => ", unntatt ",
+            (Language::Norwegian, false) => ", men bare ",
+        }
+    }
+
+    /// Replaces the month and weekday names of the language in a schedule
+    /// with the English abbreviations cron understands. Names can be
+    /// shortened as long as they stay unambiguous, like "Mo-Fr" or "mär".
+    pub fn translate_names(self, schedule: &str) -> String {
+        if self == Language::English {
+            return schedule.to_string();
+        }
+        schedule
+            .split(' ')
+            .enumerate()
+            .map(|(i, field)| match i {
+                3 => replace_names(field, &self.months(), MONTH_NAMES),
+                4 => replace_names(field, &self.weekdays(), WEEK_DAY_NAMES),
+                _ => field.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn starts_with_vowel(word: &str) -> bool {
+    fold(word).starts_with(['a', 'e', 'i', 'o', 'u', 'y'])
+}
+
+/// Lowercases a name and strips its accents.
+fn fold(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ä' | 'å' | 'æ' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'î' | 'ï' => 'i',
+            'ó' | 'ô' | 'ö' | 'ø' => 'o',
+            'ú' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            'ñ' => 'n',
+            c => c,
+        })
+        .collect()
+}
+
+fn replace_names(field: &str, names: &[&str], english: &[&str]) -> String {
+    let mut result = String::new();
+    let mut rest = field;
+    while let Some(start) = rest.find(char::is_alphabetic) {
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(|c: char| !c.is_alphabetic())
+            .map_or(rest.len(), |len| start + len);
+        let word = &rest[start..end];
+        match find_name(names, word) {
+            Some(i) => result.push_str(english[i]),
+            None => result.push_str(word),
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// The index of the only name starting with `word`, if any.
+fn find_name(names: &[&str], word: &str) -> Option<usize> {
+    let word = fold(word);
+    if word.chars().count() < 2 {
+        return None;
+    }
+    let mut found = names
+        .iter()
+        .enumerate()
+        .filter(|(_, name)| fold(name).starts_with(&word));
+    match (found.next(), found.next()) {
+        (Some((i, _)), None) => Some(i),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron::Schedule;
+
+    const LANGUAGES: [Language; 5] = [
+        Language::English,
+        Language::German,
+        Language::French,
+        Language::Spanish,
+        Language::Norwegian,
+    ];
+
+    #[test]
+    fn language_from_locale() {
+        assert_eq!(Language::from_locale("de_AT.UTF-8"), Language::German);
+        assert_eq!(Language::from_locale("nb_NO.UTF-8"), Language::Norwegian);
+        assert_eq!(Language::from_locale("fr"), Language::French);
+        assert_eq!(Language::from_locale("ja_JP.UTF-8"), Language::English);
+        assert_eq!(Language::from_locale("C"), Language::English);
+    }
+
+    #[test]
+    fn joins() {
+        let items = ["1", "2", "3"].map(String::from);
+        assert_eq!(Language::English.join(&items), "1, 2, and 3");
+        assert_eq!(Language::German.join(&items), "1, 2 und 3");
+        assert_eq!(Language::Spanish.join(&items[1..]), "2 y 3");
+    }
+
+    #[test]
+    fn localized_names() {
+        assert_eq!(
+            Language::German.translate_names("0 9 * Mär,Okt Mo-Fr"),
+            "0 9 * MAR,OCT MON-FRI"
+        );
+        assert_eq!(
+            Language::French.translate_names("0 9 * août samedi"),
+            "0 9 * AUG SAT"
+        );
+        assert_eq!(
+            Language::Spanish.translate_names("0 9 * * mié"),
+            "0 9 * * WED"
+        );
+        assert_eq!(
+            Language::Norwegian.translate_names("0 9 * * lø,SØN"),
+            "0 9 * * SAT,SUN"
+        );
+        // Single letters are too short to tell names apart.
+        assert_eq!(Language::Spanish.translate_names("0 9 * * m"), "0 9 * * m");
+    }
+
+    #[test]
+    fn english_names_still_work() {
+        for language in LANGUAGES {
+            for (i, name) in MONTH_NAMES.iter().chain(WEEK_DAY_NAMES).enumerate() {
+                let input = if i < 12 {
+                    format!("* * * {name} *")
+                } else {
+                    format!("* * * * {name}")
+                };
+                assert_eq!(
+                    Schedule::from_str(&language.translate_names(&input)),
+                    Schedule::from_str(&input),
+                    "{name} in {language:?}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! Parsing and describing cron schedules, as used by the `explaincron`
+//! command.
+//!
+//! ```
+//! use explaincron::cron::{self, DescribeOptions, Schedule};
+//!
+//! let schedule = Schedule::from_str("30 9 * * 1-5").unwrap();
+//! assert_eq!(
+//!     cron::describe(&schedule, &DescribeOptions::default()),
+//!     "At 09:30 on weekdays."
+//! );
+//! ```
+
+// Parsers are inherent `from_str` functions with `String` errors, like the
+// rest of the crate's errors.
+#![allow(clippy::should_implement_trait)]
+
+pub mod cron;
+pub mod datefmt;
+pub mod language;
+pub mod lint;
+pub mod natural;
+pub mod schedule_set;
+#[cfg(feature = "serde")]
+pub mod serialization;
@@ -0,0 +1,604 @@
+//! Checks for schedules that are valid cron but probably not what was meant,
+//! like `* 2 * * *` for "at 02:00". Each rule has an ID, by which it can be
+//! suppressed or made more or less severe.
+
+use time::macros::date;
+
+use crate::cron::{join_oxford, Days, Minute, Schedule, Value};
+use crate::language::{Field, Language};
+
+const MONTH_DAYS: [i32; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Note,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// What a rule found: the fields responsible and what's wrong with them.
+type Finding = (Vec<Field>, String);
+
+pub struct Rule {
+    pub id: &'static str,
+    /// How serious a finding is unless configured otherwise.
+    pub severity: Severity,
+    pub summary: &'static str,
+    check: fn(&Schedule) -> Vec<Finding>,
+}
+
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "every-minute",
+        severity: Severity::Warning,
+        summary: "runs every minute of some hours, like '* 2 * * *'",
+        check: every_minute,
+    },
+    Rule {
+        id: "either-day",
+        severity: Severity::Warning,
+        summary: "restricts both days, so runs on days matching either",
+        check: either_day,
+    },
+    Rule {
+        id: "never-runs",
+        severity: Severity::Error,
+        summary: "picks days that none of its months have",
+        check: never_runs,
+    },
+    Rule {
+        id: "missing-days",
+        severity: Severity::Warning,
+        summary: "picks days, like the 31st, that some of its months don't have",
+        check: missing_days,
+    },
+    Rule {
+        id: "uneven-step",
+        severity: Severity::Warning,
+        summary: "has a step that starts over before running a whole step",
+        check: uneven_step,
+    },
+    Rule {
+        id: "duplicate-values",
+        severity: Severity::Warning,
+        summary: "lists a value more than once, like '0,SUN'",
+        check: duplicate_values,
+    },
+    Rule {
+        id: "top-of-hour",
+        severity: Severity::Note,
+        summary: "starts at minute 0, along with most other jobs",
+        check: top_of_hour,
+    },
+];
+
+/// The rule with the ID `id`.
+pub fn rule(id: &str) -> Result<&'static Rule, String> {
+    RULES.iter().find(|rule| rule.id == id).ok_or_else(|| {
+        format!(
+            "'{id}' is not a lint rule ({})",
+            RULES.iter().map(|r| r.id).collect::<Vec<_>>().join(", ")
+        )
+    })
+}
+
+/// A finding of one rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub fields: Vec<Field>,
+    pub message: String,
+}
+
+/// Which rules to check, and how severe their findings are.
+#[derive(Debug, Clone, Default)]
+pub struct Linter {
+    overrides: Vec<(&'static str, Option<Severity>)>,
+}
+
+impl Linter {
+    /// Reports the rule `id` with `severity`, or not at all if it's `None`.
+    pub fn set(&mut self, id: &str, severity: Option<Severity>) -> Result<(), String> {
+        let id = rule(id)?.id;
+        self.overrides.retain(|(other, _)| *other != id);
+        self.overrides.push((id, severity));
+        Ok(())
+    }
+
+    /// The severity the findings of `rule` are reported with, if at all.
+    pub fn severity(&self, rule: &Rule) -> Option<Severity> {
+        self.overrides
+            .iter()
+            .find(|(id, _)| *id == rule.id)
+            .map_or(Some(rule.severity), |(_, severity)| *severity)
+    }
+
+    /// The findings of every rule that isn't suppressed, in the order of
+    /// `RULES`.
+    pub fn lint(&self, schedule: &Schedule) -> Vec<Lint> {
+        RULES
+            .iter()
+            .filter_map(|rule| Some((rule, self.severity(rule)?)))
+            .flat_map(|(rule, severity)| {
+                (rule.check)(schedule)
+                    .into_iter()
+                    .map(move |(fields, message)| Lint {
+                        rule: rule.id,
+                        severity,
+                        fields,
+                        message,
+                    })
+            })
+            .collect()
+    }
+}
+
+fn every_minute(schedule: &Schedule) -> Vec<Finding> {
+    if schedule.minute.value != Value::Wildcard || schedule.hour.value == Value::Wildcard {
+        return vec![];
+    }
+    let hours = schedule.hour.value.values(0, 23);
+    let on_the_hour = Schedule {
+        minute: Minute {
+            value: Value::Single(0),
+        },
+        ..schedule.clone()
+    };
+    let message = match hours[..] {
+        [hour] => format!(
+            "runs every minute from {hour:02}:00 to {hour:02}:59; write '{on_the_hour}' to run once at {hour:02}:00"
+        ),
+        _ => format!(
+            "runs every minute of {} hours a day; write '{on_the_hour}' to run once at the start of each",
+            hours.len()
+        ),
+    };
+    vec![(vec![Field::Minute, Field::Hour], message)]
+}
+
+fn either_day(schedule: &Schedule) -> Vec<Finding> {
+    if !schedule.runs_on_either_day() {
+        return vec![];
+    }
+    vec![(
+        vec![Field::DayOfMonth, Field::DayOfWeek],
+        "runs on days matching either the day-of-month or the day-of-week, not only on days matching both"
+            .to_string(),
+    )]
+}
+
+/// The first day of the month the schedule runs on, and the selected
+/// months that don't have it, when days are only picked by day of the month.
+fn months_without_first_day(schedule: &Schedule) -> Option<(i32, Vec<i32>)> {
+    if schedule.day_of_month.value == Value::Wildcard
+        || schedule.day_of_week.value != Value::Wildcard
+    {
+        return None;
+    }
+    let first_day = schedule.day_of_month.value.values(1, 31)[0];
+    let missing = schedule
+        .month
+        .value
+        .values(1, 12)
+        .into_iter()
+        .filter(|month| first_day > MONTH_DAYS[(*month - 1) as usize])
+        .collect();
+    Some((first_day, missing))
+}
+
+fn never_runs(schedule: &Schedule) -> Vec<Finding> {
+    match months_without_first_day(schedule) {
+        Some((first_day, missing)) if missing.len() == schedule.month.value.values(1, 12).len() => {
+            vec![(
+                vec![Field::DayOfMonth, Field::Month],
+                format!("never runs, since no selected month has a day {first_day}"),
+            )]
+        }
+        _ => vec![],
+    }
+}
+
+/// The days in `month`, where February has 29 only in leap years.
+fn month_length(month: i32, leap_year: bool) -> i32 {
+    match month {
+        2 if !leap_year => 28,
+        _ => MONTH_DAYS[(month - 1) as usize],
+    }
+}
+
+fn missing_days(schedule: &Schedule) -> Vec<Finding> {
+    let Some((first_day, missing)) = months_without_first_day(schedule) else {
+        return vec![];
+    };
+    let months = schedule.month.value.values(1, 12);
+    if missing.len() == months.len() {
+        // never-runs reports those.
+        return vec![];
+    }
+    let days = schedule.day_of_month.value.values(1, 31);
+    let fields = || vec![Field::DayOfMonth, Field::Month];
+    let mut findings = Vec::new();
+    if !missing.is_empty() {
+        findings.push((
+            fields(),
+            format!(
+                "does not run in {}, which {} fewer than {first_day} days",
+                join_oxford(&missing, |month| Language::English
+                    .month(*month)
+                    .to_string()),
+                if missing.len() == 1 { "has" } else { "have" }
+            ),
+        ));
+    }
+    let february = months.contains(&2) && !missing.contains(&2);
+    if february && first_day == 29 {
+        findings.push((
+            fields(),
+            match months == [2] {
+                true => "only runs in leap years".to_string(),
+                false => "does not run in February except in leap years".to_string(),
+            },
+        ));
+    }
+
+    // Days written out that some months which otherwise run don't have. Steps
+    // and ranges run on whichever of their days each month has.
+    if !matches!(schedule.day_of_month.value, Value::List(_)) {
+        return findings;
+    }
+    if february && first_day < 29 && days.contains(&29) {
+        findings.push((
+            fields(),
+            "does not run on day 29 in February except in leap years".to_string(),
+        ));
+    }
+    let mut groups: Vec<(Vec<i32>, Vec<i32>)> = Vec::new();
+    for month in months
+        .iter()
+        .filter(|month| first_day <= month_length(**month, false))
+    {
+        let absent = days
+            .iter()
+            .copied()
+            .filter(|day| *day > month_length(*month, true))
+            .collect::<Vec<_>>();
+        if absent.is_empty() {
+            continue;
+        }
+        match groups.iter_mut().find(|(days, _)| *days == absent) {
+            Some((_, months)) => months.push(*month),
+            None => groups.push((absent, vec![*month])),
+        }
+    }
+    for (absent, months) in groups {
+        findings.push((
+            fields(),
+            format!(
+                "does not run on {} {} in {}",
+                if absent.len() == 1 { "day" } else { "days" },
+                join_oxford(&absent, i32::to_string),
+                join_oxford(&months, |month| Language::English.month(*month).to_string()),
+            ),
+        ));
+    }
+    findings
+}
+
+fn plural(n: i32, unit: &str) -> String {
+    if n == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{n} {unit}s")
+    }
+}
+
+/// The closest steps either side of `step` that divide `length`.
+fn divisors(length: i32, step: i32) -> String {
+    let below = (2..step).rev().find(|d| length % d == 0);
+    let above = (step + 1..length).find(|d| length % d == 0);
+    below
+        .into_iter()
+        .chain(above)
+        .map(|d| format!("*/{d}"))
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+/// Steps start over at the end of their field, like `*/7` at the top of every
+/// hour, so unless the step divides the field the last gap is shorter than
+/// the step. Explains those gaps and how to run evenly instead.
+/// The shorter gap only happens when the enclosing field also runs in the
+/// next hour, day or month, so it isn't reported otherwise.
+fn uneven_step(schedule: &Schedule) -> Vec<Finding> {
+    // A leap-year cycle of days from 2000-01-01 covers every weekday and day
+    // of the month.
+    let running = Days::new(schedule);
+    let consecutive_days = std::iter::successors(Some(date!(2000 - 01 - 01)), |d| d.next_day())
+        .take(4 * 365 + 2)
+        .map(|day| running.contains(day))
+        .collect::<Vec<_>>()
+        .windows(2)
+        .any(|w| w[0] && w[1]);
+    let hours = schedule.hour.value.values(0, 23);
+    let consecutive_hours = hours.windows(2).any(|w| w[1] == w[0] + 1)
+        || (consecutive_days && hours.first() == Some(&0) && hours.last() == Some(&23));
+    let months = schedule.month.value.values(1, 12);
+
+    let mut findings = Vec::new();
+    let mut check = |field: Field,
+                     value: &Value,
+                     (min, max): (i32, i32),
+                     wraps: bool,
+                     period: &str,
+                     unit: &str,
+                     name: &dyn Fn(i32) -> String| {
+        if !wraps {
+            return;
+        }
+        let step = match value {
+            Value::Step(_, step) => *step,
+            Value::Range(start, stop, Some(step)) if *start == min && *stop == max => *step,
+            _ => return,
+        };
+        let length = max - min + 1;
+        let values = value.values(min, max);
+        // Steps starting later than one step in are windows, not cadences.
+        if values.len() < 2 || values[0] - min >= step {
+            return;
+        }
+        let (first, last) = (values[0], values[values.len() - 1]);
+        let gap = length - last + first;
+        if gap == step {
+            return;
+        }
+        let fix = match divisors(length, step) {
+            steps if steps.is_empty() => format!("list the {unit}s instead to make the gaps plain"),
+            steps => format!("a step that divides {length}, like {steps}, runs evenly"),
+        };
+        findings.push((
+            vec![field],
+            format!(
+                "'{value}' in the {} field starts over every {period}, so runs are {} apart except {} from {} to {}; {fix}",
+                field.name().replace('_', "-"),
+                plural(step, unit),
+                plural(gap, unit),
+                name(last),
+                name(first),
+            ),
+        ));
+    };
+
+    check(
+        Field::Minute,
+        &schedule.minute.value,
+        (0, 59),
+        consecutive_hours,
+        "hour",
+        "minute",
+        &|m| format!("minute {m}"),
+    );
+    check(
+        Field::Hour,
+        &schedule.hour.value,
+        (0, 23),
+        consecutive_days,
+        "day",
+        "hour",
+        &|h| format!("{h:02}:00"),
+    );
+    check(
+        Field::Month,
+        &schedule.month.value,
+        (1, 12),
+        true,
+        "year",
+        "month",
+        &|m| Language::English.month(m).to_string(),
+    );
+    check(
+        Field::DayOfWeek,
+        &schedule.day_of_week.value,
+        (0, 6),
+        true,
+        "week",
+        "day",
+        &|d| Language::English.weekday(d).to_string(),
+    );
+
+    // Months have different lengths, so the gap at the end of the month
+    // depends on the month. Only meaningful when days aren't also picked by
+    // day of the week.
+    let day_of_month = &schedule.day_of_month.value;
+    let days = day_of_month.values(1, 31);
+    let step = match day_of_month {
+        Value::Step(_, step) | Value::Range(1, 31, Some(step)) => Some(*step),
+        _ => None,
+    }
+    .filter(|step| days.len() > 1 && days[0] - 1 < *step);
+    if let (Some(step), Value::Wildcard) = (step, &schedule.day_of_week.value) {
+        // Only months followed by another selected month start over.
+        let mut lengths = months
+            .iter()
+            .filter(|month| months.contains(&(*month % 12 + 1)))
+            .flat_map(|month| match month {
+                2 => vec![28, 29],
+                _ => vec![MONTH_DAYS[(*month - 1) as usize]],
+            })
+            .collect::<Vec<_>>();
+        lengths.sort();
+        lengths.dedup();
+        let uneven = lengths
+            .iter()
+            .filter_map(|length| {
+                let last = *days.iter().filter(|day| *day <= length).max()?;
+                let gap = length - last + days[0];
+                (gap != step).then(|| {
+                    format!(
+                        "{} from day {last} to day {} in {length}-day months",
+                        plural(gap, "day"),
+                        days[0]
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        if !uneven.is_empty() {
+            findings.push((
+                vec![Field::DayOfMonth],
+                format!(
+                    "'{day_of_month}' in the day-of-month field starts over every month, so runs are {} apart except {}; no step divides every month, so to run evenly, run daily and check the date in the command, like [ $(( $(date +\\%s) / 86400 \\% {step} )) -eq 0 ], where cron needs each % written as \\%",
+                    plural(step, "day"),
+                    join_oxford(&uneven, |s| s.clone()),
+                ),
+            ));
+        }
+    }
+
+    findings
+}
+
+fn duplicate_values(schedule: &Schedule) -> Vec<Finding> {
+    let fields = [
+        (Field::Minute, &schedule.minute.value),
+        (Field::Hour, &schedule.hour.value),
+        (Field::DayOfMonth, &schedule.day_of_month.value),
+        (Field::Month, &schedule.month.value),
+        (Field::DayOfWeek, &schedule.day_of_week.value),
+    ];
+    fields
+        .into_iter()
+        .filter_map(|(field, value)| {
+            let Value::List(list) = value else {
+                return None;
+            };
+            // Sunday is both 0 and 7 (SUN).
+            let same = |a: i32, b: i32| match field {
+                Field::DayOfWeek => a % 7 == b % 7,
+                _ => a == b,
+            };
+            let repeated = list
+                .iter()
+                .enumerate()
+                .filter(|(i, a)| list[..*i].iter().any(|b| same(**a, *b)))
+                .map(|(_, a)| *a)
+                .collect::<Vec<_>>();
+            let name = |n: &i32| match field {
+                Field::Month => Language::English.month(*n).to_string(),
+                Field::DayOfWeek => Language::English.weekday(*n).to_string(),
+                _ => n.to_string(),
+            };
+            (!repeated.is_empty()).then(|| {
+                (
+                    vec![field],
+                    format!(
+                        "'{value}' in the {} field lists {} more than once",
+                        field.name().replace('_', "-"),
+                        join_oxford(&repeated, name)
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+fn top_of_hour(schedule: &Schedule) -> Vec<Finding> {
+    let minute = &schedule.minute.value;
+    if *minute == Value::Wildcard || !minute.values(0, 59).contains(&0) {
+        return vec![];
+    }
+    vec![(
+        vec![Field::Minute],
+        "starts at minute 0, like most other jobs; another minute, like 17, spreads the load"
+            .to_string(),
+    )]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(schedule: &str) -> Vec<(&'static str, String)> {
+        Linter::default()
+            .lint(&Schedule::from_str(schedule).unwrap())
+            .into_iter()
+            .map(|lint| (lint.rule, lint.message))
+            .collect()
+    }
+
+    #[test]
+    fn finds_mistakes() {
+        assert!(lint("30 9 * * 1-5").is_empty());
+        assert_eq!(
+            lint("* 2 * * *"),
+            vec![(
+                "every-minute",
+                "runs every minute from 02:00 to 02:59; write '0 2 * * *' to run once at 02:00"
+                    .to_string()
+            )]
+        );
+        assert_eq!(
+            lint("* 9-17 * * 1-5")[0].1,
+            "runs every minute of 9 hours a day; write '0 9-17 * * 1-5' to run once at the start of each"
+        );
+        assert_eq!(
+            lint("15 0 * * 0,SUN"),
+            vec![(
+                "duplicate-values",
+                "'0,7' in the day-of-week field lists Sunday more than once".to_string()
+            )]
+        );
+        assert_eq!(
+            lint("15 0 * 1,1,3 *")[0].1,
+            "'1,1,3' in the month field lists January more than once"
+        );
+        assert_eq!(
+            lint("15 0 * * 0,7")[0].1,
+            "'0,7' in the day-of-week field lists Sunday more than once"
+        );
+        assert_eq!(lint("0 3 30 2 *")[0].0, "never-runs");
+        assert_eq!(lint("0 3 30 2 *")[1].0, "top-of-hour");
+        assert_eq!(lint("5 3 31 * *")[0].0, "missing-days");
+        assert_eq!(lint("5 3 1 * 1")[0].0, "either-day");
+        assert_eq!(lint("*/7 3-4 * * *")[0].0, "uneven-step");
+        // 03:56 is followed by 03:00 the next day.
+        assert!(lint("*/7 3 * * *")
+            .iter()
+            .all(|(id, _)| *id != "uneven-step"));
+    }
+
+    #[test]
+    fn configures_rules() {
+        let schedule = Schedule::from_str("0 0 31 * *").unwrap();
+        let mut linter = Linter::default();
+        assert_eq!(
+            linter
+                .lint(&schedule)
+                .iter()
+                .map(|lint| (lint.rule, lint.severity))
+                .collect::<Vec<_>>(),
+            vec![
+                ("missing-days", Severity::Warning),
+                ("top-of-hour", Severity::Note)
+            ]
+        );
+
+        linter.set("top-of-hour", None).unwrap();
+        linter.set("missing-days", Some(Severity::Error)).unwrap();
+        let lints = linter.lint(&schedule);
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].severity, Severity::Error);
+        assert_eq!(lints[0].fields, vec![Field::DayOfMonth, Field::Month]);
+
+        assert!(linter.set("no-such-rule", None).is_err());
+    }
+}
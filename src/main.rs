@@ -1,13 +1,55 @@
+mod analysis;
+mod breakdown;
+mod calendar;
+mod check;
 mod clap_app;
-mod cron;
+mod compare;
+mod crontab;
+mod dependency;
+mod duration;
+mod holidays;
+mod inference;
+mod json;
+mod overlap;
+mod policy;
+mod rebalance;
+mod statistics;
+mod synthesis;
 
+use std::io::{IsTerminal, Read};
+
+use clap::ArgMatches;
 use cron::{DayOfMonth, DayOfWeek, Hour, Minute, Month, Schedule};
+use datefmt::{Clock, DateStyle, DateTimeFormat};
+use explaincron::{cron, datefmt, language, lint, natural, schedule_set};
+use language::Language;
+use schedule_set::ScheduleSet;
 use time::ext::NumericalDuration;
-use time::{OffsetDateTime, UtcOffset};
+use time::{Duration, OffsetDateTime, UtcOffset};
 
 fn main() -> Result<(), String> {
     let matches = clap_app::app().get_matches();
 
+    match matches.subcommand() {
+        ("timeline", Some(sub_matches)) => timeline(sub_matches),
+        ("analyze", Some(sub_matches)) => analyze(sub_matches),
+        ("rebalance", Some(sub_matches)) => rebalance(sub_matches),
+        ("overlap", Some(sub_matches)) => overlap(sub_matches),
+        ("depends", Some(sub_matches)) => depends(sub_matches),
+        ("diff", Some(sub_matches)) => diff(sub_matches),
+        ("normalize", Some(sub_matches)) => normalize(sub_matches),
+        ("combine", Some(sub_matches)) => combine(sub_matches),
+        ("calendar", Some(sub_matches)) => calendar(sub_matches),
+        ("every", Some(sub_matches)) => every(sub_matches),
+        ("write", Some(sub_matches)) => write(sub_matches),
+        ("infer", Some(sub_matches)) => infer(sub_matches),
+        ("lint", Some(sub_matches)) => lint(sub_matches),
+        ("check", Some(sub_matches)) => check(sub_matches),
+        _ => explain(&matches),
+    }
+}
+
+fn explain(matches: &ArgMatches) -> Result<(), String> {
     let schedule = if matches.is_present("random") {
         Schedule {
             minute: Minute {
@@ -28,40 +70,766 @@ fn main() -> Result<(), String> {
         }
     } else {
         let first_arg = matches.value_of("SCHEDULE").unwrap();
-        Schedule::from_str(first_arg).unwrap()
+        // Checked here rather than by clap, which doesn't know the language,
+        // but reported the same way.
+        Schedule::from_str(&language(matches)?.translate_names(first_arg)).unwrap_or_else(|e| {
+            clap::Error::with_description(
+                &format!("Invalid value for '<SCHEDULE>': {e}"),
+                clap::ErrorKind::ValueValidation,
+            )
+            .exit()
+        })
     };
 
+    let options = cron::DescribeOptions {
+        verbose: matches.is_present("verbose"),
+        clock: clock(matches)?,
+        language: language(matches)?,
+    };
+    let num_next_occurrence = num_next_occurrence(matches);
+    let holidays = holidays(matches)?;
+    let occurrences = |from_time| -> Box<dyn Iterator<Item = OffsetDateTime> + '_> {
+        if matches.is_present("skip-holidays") {
+            Box::new(holidays::effective_occurrences(
+                from_time, &schedule, &holidays,
+            ))
+        } else {
+            Box::new(cron::occurrences(from_time, &schedule))
+        }
+    };
+
+    if matches.value_of("output") == Some("json") {
+        let occurrences = occurrences(now()?)
+            .take(num_next_occurrence)
+            .collect::<Vec<_>>();
+        println!("{}", json::explanation(&schedule, &options, &occurrences));
+        return Ok(());
+    }
+
     if matches.is_present("random") {
-        println!("{}", schedule.to_string());
+        println!("{}", schedule);
+    }
+    if matches.is_present("breakdown") {
+        let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        println!("{}", breakdown::breakdown(&schedule, &options, color));
+    } else {
+        println!("{}", cron::describe(&schedule, &options));
+    }
+    for warning in cron::warnings(&schedule) {
+        eprintln!("warning: {warning}");
+    }
+    if matches.is_present("stats") {
+        print_statistics(&schedule);
     }
-    println!("{}", cron::human_readable(&schedule));
 
     if matches.is_present("random") {
-        println!("{}", schedule.to_string());
+        println!("{}", schedule);
     }
 
-    let num_next_occurrence = matches
-        .value_of("num-next-occurrence")
-        .map(|s| s.parse::<usize>().unwrap())
+    let format = datetime_format(matches)?;
+    if holidays.blackouts.is_empty() {
+        let mut next = now()?;
+
+        for _ in 1..=num_next_occurrence {
+            next = cron::next_occurrence(next, &schedule)?;
+
+            println!("{}", format.format(next));
+
+            next = next.saturating_add(1.seconds());
+        }
+        return Ok(());
+    }
+
+    for next in occurrences(now()?).take(num_next_occurrence) {
+        match holidays.on(next.date()) {
+            Some(blackout) => println!(
+                "{} (on {})",
+                format.format(next),
+                blackout.name.as_deref().unwrap_or("a blocked out day")
+            ),
+            None => println!("{}", format.format(next)),
+        }
+    }
+
+    Ok(())
+}
+
+fn print_statistics(schedule: &Schedule) {
+    let Some(statistics) = statistics::statistics(schedule) else {
+        println!("\nNever runs.");
+        return;
+    };
+    println!("\nRuns per       min       max        mean");
+    for (period, count) in [
+        ("hour", &statistics.per_hour),
+        ("day", &statistics.per_day),
+        ("week", &statistics.per_week),
+        ("month", &statistics.per_month),
+        ("year", &statistics.per_year),
+    ] {
+        println!(
+            "  {period:<8}{:>9}{:>10}{:>12.2}",
+            count.min, count.max, count.mean
+        );
+    }
+    println!(
+        "Gaps between runs: {} to {}, {} on average ({})",
+        duration::format(statistics.min_gap),
+        duration::format(statistics.max_gap),
+        duration::format(statistics.mean_gap),
+        if statistics.is_uniform() {
+            "uniform"
+        } else {
+            "not uniform"
+        }
+    );
+}
+
+fn timeline(matches: &ArgMatches) -> Result<(), String> {
+    let format = datetime_format(matches)?;
+    let input = read_input(matches.value_of("CRONTAB").unwrap())?;
+    let entries = crontab::parse(&input)?;
+
+    for (next, entry) in crontab::timeline(&entries, now()?, num_next_occurrence(matches)) {
+        println!("{}  {}", format.format(next), entry.label());
+    }
+
+    Ok(())
+}
+
+fn analyze(matches: &ArgMatches) -> Result<(), String> {
+    let format = datetime_format(matches)?;
+    let input = read_input(matches.value_of("CRONTAB").unwrap())?;
+    let entries = crontab::parse(&input)?;
+    let schedules = entries.iter().map(|e| &e.schedule).collect::<Vec<_>>();
+    let window = duration::parse(matches.value_of("window").unwrap())?;
+    let top = matches.value_of("top").unwrap().parse::<usize>().unwrap();
+
+    let analysis = analysis::analyze(&schedules, now()?, window, top)?;
+
+    println!(
+        "{} runs of {} jobs from {} to {}",
+        analysis.total_runs,
+        entries.len(),
+        format.format(analysis.start),
+        format.format(analysis.end)
+    );
+
+    println!("\nBusiest minutes:");
+    if analysis.peaks.is_empty() {
+        println!("  no two jobs start in the same minute");
+    }
+    for (minute, starting) in &analysis.peaks {
+        println!(
+            "  {}  {} jobs: {}",
+            format.format(*minute),
+            starting.len(),
+            labels(&entries, starting)
+        );
+    }
+
+    println!("\nStarts per minute of hour:");
+    let rows = analysis
+        .minute_histogram
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0)
+        .map(|(minute, count)| (format!(":{minute:0>2}"), *count))
+        .collect::<Vec<_>>();
+    print!("{}", analysis::histogram(&rows));
+
+    println!("\nStarts per hour of day:");
+    let rows = analysis
+        .hour_histogram
+        .iter()
+        .enumerate()
+        .map(|(hour, count)| (format!("{hour:0>2}"), *count))
+        .collect::<Vec<_>>();
+    print!("{}", analysis::histogram(&rows));
+
+    if !analysis.coinciding.is_empty() {
+        println!("\nJobs that always start together:");
+        for group in &analysis.coinciding {
+            println!("  {}", labels(&entries, group));
+        }
+    }
+
+    Ok(())
+}
+
+fn rebalance(matches: &ArgMatches) -> Result<(), String> {
+    let input = read_input(matches.value_of("CRONTAB").unwrap())?;
+    let entries = crontab::parse(&input)?;
+    let window = duration::parse(matches.value_of("window").unwrap())?;
+    let from_time = now()?;
+
+    let suggestions = rebalance::rebalance(&entries, from_time, window)?;
+
+    for (i, line) in input.lines().enumerate() {
+        let suggestion = entries
+            .iter()
+            .zip(&suggestions)
+            .find(|(entry, _)| entry.line == i + 1)
+            .and_then(|(entry, suggestion)| suggestion.as_ref().map(|s| (entry, s)));
+        match suggestion {
+            Some((entry, schedule)) => println!("{}", entry.with_schedule(line, schedule)),
+            None => println!("{}", line),
+        }
+    }
+
+    let after = entries
+        .iter()
+        .zip(&suggestions)
+        .map(|(entry, suggestion)| suggestion.as_ref().unwrap_or(&entry.schedule))
+        .collect::<Vec<_>>();
+    let before = entries.iter().map(|e| &e.schedule).collect::<Vec<_>>();
+    for (title, schedules) in [("Before", before), ("After", after)] {
+        let analysis = analysis::analyze(&schedules, from_time, window, 0)?;
+        let rows = analysis
+            .minute_histogram
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(minute, count)| (format!(":{minute:0>2}"), *count))
+            .collect::<Vec<_>>();
+        println!("\n# {title}: starts per minute of hour");
+        for line in analysis::histogram(&rows).lines() {
+            println!("#{line}");
+        }
+    }
+
+    Ok(())
+}
+
+fn overlap(matches: &ArgMatches) -> Result<(), String> {
+    let format = datetime_format(matches)?;
+    let runtime = matches
+        .value_of("duration")
+        .map(duration::parse)
+        .transpose()?;
+    let window = duration::parse(matches.value_of("window").unwrap())?;
+    let max_pairs = matches
+        .value_of("num-overlaps")
+        .unwrap()
+        .parse::<usize>()
         .unwrap();
+    let from_time = now()?;
 
-    let odt: OffsetDateTime = std::time::SystemTime::now().into();
-    let offset = UtcOffset::current_local_offset().map_err(|_e| format!("date error"))?;
-    let mut next = odt.to_offset(offset);
+    if let Some(schedule) = matches.value_of("SCHEDULE") {
+        let runtime = runtime.ok_or("--duration is needed to check a single schedule")?;
+        let overlaps = overlap::self_overlaps(
+            &Schedule::from_str(schedule)?,
+            runtime,
+            from_time,
+            window,
+            max_pairs,
+        );
+        print_self_overlaps(schedule, runtime, &overlaps, &format);
+        return Ok(());
+    }
+
+    let input = read_input(matches.value_of("crontab").unwrap())?;
+    let entries = crontab::parse(&input)?;
+    let runtime_of = |entry: &crontab::Entry| match entry.annotation("duration") {
+        Some(d) => duration::parse(d)
+            .map(Some)
+            .map_err(|e| format!("line {}: {e}", entry.line)),
+        None => Ok(runtime),
+    };
+    let missing_runtime =
+        |entry: &crontab::Entry| format!("no duration given for '{}'", entry.label());
+
+    match (matches.value_of("job"), matches.value_of("with")) {
+        (Some(job), Some(other)) => {
+            let job = crontab::find(&entries, job)?;
+            let other = crontab::find(&entries, other)?;
+            let job_runtime = runtime_of(job)?.ok_or_else(|| missing_runtime(job))?;
+            let other_runtime = runtime_of(other)?.ok_or_else(|| missing_runtime(other))?;
+            let pairs = overlap::overlaps_between(
+                (&job.schedule, job_runtime),
+                (&other.schedule, other_runtime),
+                from_time,
+                window,
+                max_pairs,
+            );
+            if pairs.is_empty() {
+                println!("{} never overlaps {}", job.label(), other.label());
+            }
+            for (job_start, other_start) in pairs {
+                println!(
+                    "{} run at {} overlaps {} run at {}",
+                    job.label(),
+                    format.format(job_start),
+                    other.label(),
+                    format.format(other_start)
+                );
+            }
+        }
+        (job, _) => {
+            let selected = match job {
+                Some(job) => vec![crontab::find(&entries, job)?],
+                None => entries.iter().collect(),
+            };
+            for entry in selected {
+                let runtime = match runtime_of(entry)? {
+                    Some(runtime) => runtime,
+                    None if job.is_some() => return Err(missing_runtime(entry)),
+                    None => continue,
+                };
+                let overlaps =
+                    overlap::self_overlaps(&entry.schedule, runtime, from_time, window, max_pairs);
+                print_self_overlaps(entry.label(), runtime, &overlaps, &format);
+            }
+        }
+    }
 
-    for _ in 1..=num_next_occurrence {
-        next = cron::next_occurrence(next, &schedule)?;
+    Ok(())
+}
 
+fn print_self_overlaps(
+    name: &str,
+    runtime: Duration,
+    overlaps: &overlap::Overlaps,
+    format: &DateTimeFormat,
+) {
+    println!("{name} (runs for {})", duration::format(runtime));
+    if let Some(gap) = overlaps.min_gap {
+        println!("  Minimum gap between runs: {}", duration::format(gap));
+    }
+    if overlaps.pairs.is_empty() {
+        println!("  Never overlaps itself");
+        return;
+    }
+    println!("  Up to {} runs at once", overlaps.max_concurrent);
+    for (earlier, later) in &overlaps.pairs {
         println!(
-            "{} {:0>2}:{:0>2}:{:0>2}",
-            next.date(),
-            next.hour(),
-            next.minute(),
-            next.second()
+            "  {} starts before the run from {} has finished",
+            format.format(*later),
+            format.format(*earlier)
         );
+    }
+}
 
-        next = next.saturating_add(1.seconds());
+fn depends(matches: &ArgMatches) -> Result<(), String> {
+    let format = datetime_format(matches)?;
+    let upstream = Schedule::from_str(matches.value_of("UPSTREAM").unwrap())?;
+    let downstream = Schedule::from_str(matches.value_of("DOWNSTREAM").unwrap())?;
+    let min_lag = duration::parse(matches.value_of("lag").unwrap())?;
+    let max_lag = duration::parse(matches.value_of("within").unwrap())?;
+    if max_lag < min_lag {
+        return Err(format!(
+            "--within {} is shorter than --lag {}",
+            duration::format(max_lag),
+            duration::format(min_lag)
+        ));
+    }
+    let window = duration::parse(matches.value_of("window").unwrap())?;
+    let max_violations = matches
+        .value_of("num-violations")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap();
+
+    let violations = dependency::check(&upstream, &downstream, min_lag, max_lag, now()?, window);
+
+    if violations.is_empty() {
+        println!(
+            "Every run of '{downstream}' starts {} to {} after a run of '{upstream}'.",
+            duration::format(min_lag),
+            duration::format(max_lag)
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} of '{downstream}' {} not start {} to {} after a run of '{upstream}':",
+        if violations.len() == 1 {
+            "1 run".to_string()
+        } else {
+            format!("{} runs", violations.len())
+        },
+        if violations.len() == 1 { "does" } else { "do" },
+        duration::format(min_lag),
+        duration::format(max_lag)
+    );
+    for violation in violations.iter().take(max_violations) {
+        match violation.last_upstream {
+            Some(last) => println!(
+                "  {} (last upstream run {}, {} earlier)",
+                format.format(violation.downstream),
+                format.format(last),
+                duration::format(violation.downstream - last)
+            ),
+            None => println!(
+                "  {} (no upstream run in the previous {})",
+                format.format(violation.downstream),
+                duration::format(max_lag)
+            ),
+        }
     }
 
     Ok(())
 }
+
+fn diff(matches: &ArgMatches) -> Result<(), String> {
+    let format = datetime_format(matches)?;
+    let old = Schedule::from_str(matches.value_of("OLD").unwrap())?;
+    let new = Schedule::from_str(matches.value_of("NEW").unwrap())?;
+    let window_arg = matches.value_of("window").unwrap();
+    let window = duration::parse(window_arg)?;
+    let max_samples = matches
+        .value_of("num-samples")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap();
+
+    let comparison = compare::compare(&old, &new, now()?, window, max_samples);
+    let within = if comparison.identical {
+        String::new()
+    } else {
+        format!(" in the next {window_arg}")
+    };
+    println!(
+        "{}",
+        match comparison.relation {
+            compare::Relation::Equivalent => format!("The schedules run at the same times{within}."),
+            compare::Relation::Subset =>
+                format!("The new schedule only runs at times the old one does{within}, but not all of them."),
+            compare::Relation::Superset =>
+                format!("The new schedule runs at all the times the old one does{within}, and more."),
+            compare::Relation::Overlapping =>
+                format!("The schedules share {} runs{within}.", comparison.common),
+            compare::Relation::Disjoint => format!("The schedules have no runs in common{within}."),
+        }
+    );
+
+    for (verb, difference) in [
+        ("additionally runs", &comparison.gained),
+        ("no longer runs", &comparison.lost),
+    ] {
+        if difference.count == 0 {
+            continue;
+        }
+        println!(
+            "\nThe new version {verb} {} {}, {}:",
+            difference.count,
+            if difference.count == 1 {
+                "time"
+            } else {
+                "times"
+            },
+            difference.summary()
+        );
+        for run in &difference.first {
+            println!("  {}", format.format(*run));
+        }
+    }
+
+    Ok(())
+}
+
+fn normalize(matches: &ArgMatches) -> Result<(), String> {
+    let schedule = Schedule::from_str(matches.value_of("SCHEDULE").unwrap())?.normalize();
+    if matches.is_present("names") {
+        println!("{}", schedule.to_string_with_names());
+    } else {
+        println!("{}", schedule);
+    }
+    Ok(())
+}
+
+fn combine(matches: &ArgMatches) -> Result<(), String> {
+    let set = ScheduleSet::from_str(matches.value_of("EXPRESSION").unwrap())?;
+    let options = cron::DescribeOptions {
+        clock: clock(matches)?,
+        language: language(matches)?,
+        ..Default::default()
+    };
+    println!("{}", set.describe(&options));
+
+    match set.to_cron_lines() {
+        Some(lines) if lines.is_empty() => println!("\nThe schedules never run."),
+        Some(lines) => {
+            println!("\nAs cron lines:");
+            for line in lines {
+                println!("  {line}");
+            }
+        }
+        None => println!("\nThese times can't be written as standard cron lines."),
+    }
+
+    let format = datetime_format(matches)?;
+    let occurrences = set
+        .occurrences(now()?)
+        .take(num_next_occurrence(matches))
+        .collect::<Vec<_>>();
+    if !occurrences.is_empty() {
+        println!();
+    }
+    for occurrence in occurrences {
+        println!("{}", format.format(occurrence));
+    }
+    Ok(())
+}
+
+fn calendar(matches: &ArgMatches) -> Result<(), String> {
+    let schedule = Schedule::from_str(matches.value_of("SCHEDULE").unwrap())?;
+    let months = matches
+        .value_of("months")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap();
+    let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    println!(
+        "{}",
+        calendar::calendar(&schedule, now()?.date(), months, language(matches)?, color)
+    );
+    Ok(())
+}
+
+fn every(matches: &ArgMatches) -> Result<(), String> {
+    let format = datetime_format(matches)?;
+    let cadence = synthesis::Cadence {
+        interval: duration::parse(matches.value_of("INTERVAL").unwrap())?,
+        anchor: synthesis::parse_time_of_day(matches.value_of("at").unwrap())?,
+        window: matches
+            .value_of("between")
+            .map(synthesis::parse_window)
+            .transpose()?,
+    };
+    let synthesis = cadence.synthesize(now()?)?;
+
+    for line in &synthesis.lines {
+        println!("{line}");
+    }
+    if !synthesis.is_exact() {
+        eprintln!(
+            "warning: cron restarts its steps every {}, so these lines only approximate every {}:",
+            if cadence.interval < 1.days() {
+                "day"
+            } else {
+                "month"
+            },
+            duration::format(cadence.interval)
+        );
+        for irregularity in &synthesis.irregularities {
+            eprintln!(
+                "  the gap after {} is {}",
+                format.format(irregularity.after),
+                duration::format(irregularity.gap)
+            );
+        }
+    }
+    Ok(())
+}
+
+fn write(matches: &ArgMatches) -> Result<(), String> {
+    let description = matches
+        .values_of("DESCRIPTION")
+        .unwrap()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let schedule = natural::parse(&description)?;
+    let options = cron::DescribeOptions {
+        clock: clock(matches)?,
+        language: language(matches)?,
+        ..Default::default()
+    };
+    println!("{schedule}");
+    // How the description was understood.
+    println!("{}", cron::describe(&schedule, &options));
+    Ok(())
+}
+
+fn infer(matches: &ArgMatches) -> Result<(), String> {
+    const MAX_LISTED: usize = 10;
+    let format = datetime_format(matches)?;
+    let input = read_input(matches.value_of("TIMESTAMPS").unwrap())?;
+    let runs = inference::parse_timestamps(&input, now()?.offset())?;
+    let inference = inference::infer(&runs)?;
+
+    for line in &inference.lines {
+        println!("{line}");
+    }
+    let percent = (inference.confidence * 100.0).round();
+    println!(
+        "\nConfidence: {percent}% ({})",
+        if percent >= 90.0 {
+            "high"
+        } else if percent >= 60.0 {
+            "medium"
+        } else {
+            "low"
+        }
+    );
+
+    for (what, times) in [
+        ("timestamps don't fit", &inference.outliers),
+        ("runs are missing", &inference.missed),
+    ] {
+        if times.is_empty() {
+            continue;
+        }
+        println!("\n{} {what}:", times.len());
+        for time in times.iter().take(MAX_LISTED) {
+            println!("  {}", format.format(*time));
+        }
+        if times.len() > MAX_LISTED {
+            println!("  and {} more", times.len() - MAX_LISTED);
+        }
+    }
+    Ok(())
+}
+
+fn lint(matches: &ArgMatches) -> Result<(), String> {
+    if matches.is_present("rules") {
+        for rule in lint::RULES {
+            println!("{:<18}{:<9}{}", rule.id, rule.severity.name(), rule.summary);
+        }
+        return Ok(());
+    }
+    let schedule = Schedule::from_str(matches.value_of("SCHEDULE").unwrap())?;
+    let linter = linter(matches)?;
+    let lints = linter.lint(&schedule);
+
+    if matches.value_of("output") == Some("json") {
+        println!("{}", json::lints(&schedule, &lints));
+    } else {
+        for lint in lints {
+            println!("{}[{}]: {}", lint.severity.name(), lint.rule, lint.message);
+        }
+    }
+    Ok(())
+}
+
+fn check(matches: &ArgMatches) -> Result<(), String> {
+    let checks = check::Checks {
+        linter: if matches.is_present("lint") {
+            Some(linter(matches)?)
+        } else {
+            None
+        },
+        policy: match matches.value_of("policy") {
+            Some(path) => Some(
+                policy::Policy::from_str(&read_input(path)?)
+                    .map_err(|e| format!("policy '{path}': {e}"))?,
+            ),
+            None => None,
+        },
+        from_time: now()?,
+        format: datetime_format(matches)?,
+    };
+    let mut diagnostics = Vec::new();
+    for pattern in matches.values_of("FILES").unwrap() {
+        for path in check::expand(pattern)? {
+            match read_input(&path) {
+                Ok(input) => diagnostics.extend(check::check(&path, &input, &checks)),
+                Err(message) => diagnostics.push(check::Diagnostic {
+                    path,
+                    location: None,
+                    severity: lint::Severity::Error,
+                    rule: check::UNREADABLE_FILE,
+                    message,
+                    explanation: None,
+                }),
+            }
+        }
+    }
+
+    if matches.value_of("output") == Some("sarif") {
+        println!("{}", json::sarif(&diagnostics));
+    } else {
+        for diagnostic in &diagnostics {
+            println!("{diagnostic}");
+        }
+    }
+    std::process::exit(check::exit_code(&diagnostics));
+}
+
+/// The rules to check, from `--allow`, `--warn` and `--deny`. The most
+/// severe wins when a rule is given more than once.
+fn linter(matches: &ArgMatches) -> Result<lint::Linter, String> {
+    let mut linter = lint::Linter::default();
+    for (arg, severity) in [
+        ("allow", None),
+        ("warn", Some(lint::Severity::Warning)),
+        ("deny", Some(lint::Severity::Error)),
+    ] {
+        for id in matches.values_of(arg).into_iter().flatten() {
+            linter.set(id, severity)?;
+        }
+    }
+    Ok(linter)
+}
+
+fn labels(entries: &[crontab::Entry], indices: &[usize]) -> String {
+    const MAX_LABELS: usize = 5;
+    let mut result = indices
+        .iter()
+        .take(MAX_LABELS)
+        .map(|i| entries[*i].label())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if indices.len() > MAX_LABELS {
+        result.push_str(&format!(" and {} more", indices.len() - MAX_LABELS));
+    }
+    result
+}
+
+fn num_next_occurrence(matches: &ArgMatches) -> usize {
+    matches
+        .value_of("num-next-occurrence")
+        .map(|s| s.parse::<usize>().unwrap())
+        .unwrap()
+}
+
+fn holidays(matches: &ArgMatches) -> Result<holidays::Holidays, String> {
+    let offset = now()?.offset();
+    let mut holidays = holidays::Holidays::default();
+    for path in matches.values_of("holidays").into_iter().flatten() {
+        holidays.extend(
+            holidays::Holidays::from_str(&read_input(path)?, offset)
+                .map_err(|e| format!("{path}: {e}"))?,
+        );
+    }
+    Ok(holidays)
+}
+
+fn now() -> Result<OffsetDateTime, String> {
+    let odt: OffsetDateTime = std::time::SystemTime::now().into();
+    let offset = UtcOffset::current_local_offset().map_err(|_e| "date error".to_string())?;
+    Ok(odt.to_offset(offset))
+}
+
+fn datetime_format(matches: &ArgMatches) -> Result<DateTimeFormat, String> {
+    Ok(DateTimeFormat {
+        clock: clock(matches)?,
+        date: DateStyle::from_str(matches.value_of("date").unwrap_or("iso")),
+        pattern: matches.value_of("format").map(|f| f.to_string()),
+    })
+}
+
+fn language(matches: &ArgMatches) -> Result<Language, String> {
+    match matches.value_of("lang") {
+        Some(language) => Language::from_str(language),
+        None => Ok(Language::from_env()),
+    }
+}
+
+fn clock(matches: &ArgMatches) -> Result<Clock, String> {
+    match matches.value_of("clock") {
+        Some(clock) => Clock::from_str(clock),
+        None => Ok(Clock::from_env()),
+    }
+}
+
+fn read_input(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| format!("failed to read stdin: {e}"))?;
+        return Ok(input);
+    }
+    std::fs::read_to_string(path).map_err(|e| format!("failed to read '{path}': {e}"))
+}
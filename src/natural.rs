@@ -0,0 +1,718 @@
+//! Reads schedules written in English, like "every weekday at 9:30am", the
+//! way the describer writes them and the way people usually say them.
+
+use crate::cron::{DayOfMonth, DayOfWeek, Hour, Minute, Month, Schedule, Value};
+use crate::language::{Field, Language};
+
+const FIELDS: [Field; 5] = [
+    Field::Minute,
+    Field::Hour,
+    Field::DayOfMonth,
+    Field::Month,
+    Field::DayOfWeek,
+];
+
+// Words that only make the sentence read well.
+const FILLER: &[&str] = &[
+    ",", "and", "the", "of", "if", "it", "it's", "its", "is", "run", "runs", "o'clock",
+];
+
+const NUMBERS: &[&str] = &[
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven",
+    "twelve",
+];
+
+const ORDINALS: &[&str] = &[
+    "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth",
+];
+
+fn bounds(field: Field) -> (i32, i32) {
+    match field {
+        Field::Minute => (0, 59),
+        Field::Hour => (0, 23),
+        Field::DayOfMonth => (1, 31),
+        Field::Month => (1, 12),
+        // Sunday is both 0 and 7, so ranges can end on it.
+        Field::DayOfWeek => (0, 7),
+    }
+}
+
+/// Turns a description like "every 15 minutes between 9 and 5 on Mondays" or
+/// "At 09:30 on weekdays." into a schedule. Fields that aren't mentioned run
+/// at every value, except the minute and hour, which are 0 when a larger
+/// field is mentioned: "on Mondays" runs at midnight, not every minute.
+pub fn parse(input: &str) -> Result<Schedule, String> {
+    let mut parser = Parser {
+        tokens: tokenize(input),
+        position: 0,
+        fields: Default::default(),
+    };
+    while let Some(token) = parser.next() {
+        parser.clause(&token)?;
+    }
+
+    let Some(smallest) = parser.fields.iter().position(Option::is_some) else {
+        return Err(format!("'{input}' doesn't say when to run"));
+    };
+    let [minute, hour, day_of_month, month, day_of_week] = parser.fields;
+    let default = |value: Option<Value>, field: usize| match value {
+        Some(value) => value,
+        None if field < smallest => Value::Single(0),
+        None => Value::Wildcard,
+    };
+    let schedule = Schedule {
+        minute: Minute {
+            value: default(minute, 0),
+        },
+        hour: Hour {
+            value: default(hour, 1),
+        },
+        day_of_month: DayOfMonth {
+            value: day_of_month.unwrap_or(Value::Wildcard),
+        },
+        month: Month {
+            value: month.unwrap_or(Value::Wildcard),
+        },
+        day_of_week: DayOfWeek {
+            value: day_of_week.unwrap_or(Value::Wildcard),
+        },
+    };
+    Ok(schedule.normalize())
+}
+
+/// Splits the input into lowercase words, with commas, hyphens and
+/// "am"/"pm" as words of their own.
+fn tokenize(input: &str) -> Vec<String> {
+    let input = input
+        .to_lowercase()
+        .replace("a.m.", "am")
+        .replace("p.m.", "pm")
+        .replace(',', " , ")
+        .replace('.', " ");
+    let mut tokens = Vec::new();
+    for word in input.split_whitespace() {
+        if word.starts_with(|c: char| c.is_ascii_digit())
+            && (word.ends_with("am") || word.ends_with("pm"))
+        {
+            let (time, meridiem) = word.split_at(word.len() - 2);
+            tokens.push(time.to_string());
+            tokens.push(meridiem.to_string());
+        } else if word.contains('-') && !word.starts_with("day-of-") {
+            for (i, part) in word.split('-').enumerate() {
+                if i > 0 {
+                    tokens.push("-".to_string());
+                }
+                if !part.is_empty() {
+                    tokens.push(part.to_string());
+                }
+            }
+        } else {
+            tokens.push(word.to_string());
+        }
+    }
+    tokens
+}
+
+/// Drops a plural "s", so "mondays" reads like "monday".
+fn singular(word: &str) -> &str {
+    word.strip_suffix('s').unwrap_or(word)
+}
+
+fn number(word: &str) -> Option<i32> {
+    word.parse::<i32>().ok().or_else(|| {
+        NUMBERS
+            .iter()
+            .position(|n| *n == word)
+            .map(|i| i as i32 + 1)
+    })
+}
+
+/// "3rd", "15th" or "first" through "tenth".
+fn ordinal(word: &str) -> Option<i32> {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    if !digits.is_empty() && ["st", "nd", "rd", "th"].contains(&&word[digits.len()..]) {
+        return digits.parse().ok();
+    }
+    ORDINALS
+        .iter()
+        .position(|o| *o == word)
+        .map(|i| i as i32 + 1)
+}
+
+/// Monday is 1 and Sunday 7, like the names in a schedule.
+fn weekday(word: &str) -> Option<i32> {
+    let word = singular(word);
+    (1..=7).find(|i| {
+        let name = Language::English.weekday(*i).to_lowercase();
+        word == name || (word.len() >= 3 && name.starts_with(word))
+    })
+}
+
+fn month(word: &str) -> Option<i32> {
+    (1..=12).find(|i| {
+        let name = Language::English.month(*i).to_lowercase();
+        word == name || (word.len() >= 3 && name.starts_with(word))
+    })
+}
+
+fn weekday_group(word: &str) -> Option<Value> {
+    match singular(word) {
+        "weekday" => Some(Value::Range(1, 5, None)),
+        "weekend" => Some(Value::List(vec![6, 7])),
+        _ => None,
+    }
+}
+
+/// A list of numbers from `item`, like "1, 2, and 3" or "Monday through
+/// Friday", as written.
+fn list_value(items: Vec<(i32, Option<i32>)>) -> Value {
+    match items.as_slice() {
+        [(single, None)] => Value::Single(*single),
+        [(start, Some(stop))] => Value::Range(*start, *stop, None),
+        _ => {
+            let mut values = items
+                .iter()
+                .flat_map(|(start, stop)| *start..=stop.unwrap_or(*start))
+                .collect::<Vec<_>>();
+            values.sort();
+            values.dedup();
+            Value::List(values)
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    position: usize,
+    fields: [Option<Value>; 5],
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, words: &[&str]) -> bool {
+        if self.peek().is_some_and(|token| words.contains(&token)) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    /// Runs `parse` and rewinds if it finds nothing.
+    fn attempt<T>(&mut self, parse: impl FnOnce(&mut Parser) -> Option<T>) -> Option<T> {
+        let position = self.position;
+        let result = parse(self);
+        if result.is_none() {
+            self.position = position;
+        }
+        result
+    }
+
+    /// Whether `parse` finds something here, without moving on.
+    fn looking_at<T>(&mut self, parse: impl FnOnce(&mut Parser) -> Option<T>) -> bool {
+        let position = self.position;
+        let found = parse(self).is_some();
+        self.position = position;
+        found
+    }
+
+    fn set(&mut self, field: Field, value: Value) -> Result<(), String> {
+        let slot = &mut self.fields[FIELDS.iter().position(|f| *f == field).unwrap()];
+        match slot {
+            Some(existing) if *existing != Value::Wildcard && *existing != value => Err(format!(
+                "the {} is given more than once",
+                field.name().replace('_', "-")
+            )),
+            _ => {
+                *slot = Some(value);
+                Ok(())
+            }
+        }
+    }
+
+    fn clause(&mut self, token: &str) -> Result<(), String> {
+        match token {
+            _ if FILLER.contains(&token) => Ok(()),
+            "every" | "each" => self.every(),
+            "at" => self.at(),
+            "past" => self.past(),
+            "on" => self.on(),
+            "in" => self.in_months(),
+            "between" | "from" => self.window(),
+            "hourly" => self.set(Field::Hour, Value::Wildcard),
+            "daily" | "nightly" => self.set(Field::DayOfMonth, Value::Wildcard),
+            "weekly" => self.set(Field::DayOfWeek, Value::Single(0)),
+            "monthly" => self.set(Field::DayOfMonth, Value::Single(1)),
+            "yearly" | "annually" => {
+                self.set(Field::DayOfMonth, Value::Single(1))?;
+                self.set(Field::Month, Value::Single(1))
+            }
+            _ => {
+                self.position -= 1;
+                self.bare()
+            }
+        }
+    }
+
+    /// Days, months and times without a preposition, like "mondays at 9".
+    fn bare(&mut self) -> Result<(), String> {
+        let token = self.peek().unwrap_or_default().to_string();
+        if weekday(&token).is_some() || weekday_group(&token).is_some() {
+            self.days_of_week()
+        } else if month(&token).is_some() {
+            self.in_months()
+        } else if ordinal(&token).is_some() {
+            self.days_of_month()
+        } else if self.looking_at(|p| p.time()) {
+            self.at()
+        } else {
+            Err(format!("don't know what '{token}' means here"))
+        }
+    }
+
+    /// "every 15 minutes", "every 3rd hour from 1 through 23", "every
+    /// weekday", "every other day" and so on.
+    fn every(&mut self) -> Result<(), String> {
+        let token = self.peek().unwrap_or_default().to_string();
+        let step = if token == "other" {
+            Some(2)
+        } else {
+            number(&token).or_else(|| ordinal(&token))
+        };
+        if step.is_some() {
+            self.position += 1;
+        }
+        let unit = self.peek().unwrap_or_default().to_string();
+
+        let field = match singular(&unit) {
+            "minute" => Field::Minute,
+            "hour" => Field::Hour,
+            "day" | "day-of-month" => Field::DayOfMonth,
+            "month" => Field::Month,
+            "day-of-week" => Field::DayOfWeek,
+            "week" if step.is_none() => {
+                self.position += 1;
+                return self.set(Field::DayOfWeek, Value::Single(0));
+            }
+            "year" if step.is_none() => {
+                self.position += 1;
+                self.set(Field::DayOfMonth, Value::Single(1))?;
+                return self.set(Field::Month, Value::Single(1));
+            }
+            "week" | "year" => {
+                return Err(format!("cron can't run every {} {unit}", step.unwrap()));
+            }
+            _ if step.is_none() => return self.bare(),
+            _ => return Err(format!("'{unit}' is not a unit of time")),
+        };
+        self.position += 1;
+
+        let (min, max) = bounds(field);
+        if let Some(step) = step.filter(|step| *step > max) {
+            // Steps start over every hour, day and so on, so they can't be
+            // longer than one.
+            let suggestion = match field {
+                Field::Minute => format!("; try 'explaincron every {step}m'"),
+                Field::Hour => format!("; try 'explaincron every {step}h'"),
+                Field::DayOfMonth => format!("; try 'explaincron every {step}d'"),
+                _ => String::new(),
+            };
+            return Err(format!(
+                "cron can't run every {step} {unit}, as steps can be at most {max}{suggestion}"
+            ));
+        }
+        let range = self.attempt(|p| {
+            p.eat(&["from"]).then_some(())?;
+            p.range(field)
+        });
+        let value = match (step.filter(|step| *step > 1), range) {
+            (None, None) => Value::Wildcard,
+            (Some(step), None) => Value::Step(None, step),
+            (None, Some((start, stop))) => Value::Range(start, stop, None),
+            // Describing "*/3" and "1/3" runs through the last day-of-week,
+            // Sunday, as 7.
+            (Some(step), Some((start, stop)))
+                if stop == max || (field == Field::DayOfWeek && stop == 7) =>
+            {
+                Value::Step(Some(start).filter(|start| *start != min), step)
+            }
+            (Some(step), Some((start, stop))) => Value::Range(start, stop, Some(step)),
+        };
+        self.set(field, value)
+    }
+
+    /// "1 through 5", "Monday to Friday" or "January - March".
+    fn range(&mut self, field: Field) -> Option<(i32, i32)> {
+        let start = self.item(field)?;
+        self.eat(&["through", "to", "thru", "-", "and", "until"])
+            .then_some(())?;
+        let stop = self.item(field)?;
+        // A week runs from Sunday as 0 when it starts there.
+        let start = if field == Field::DayOfWeek && start == 7 {
+            0
+        } else {
+            start
+        };
+        (start <= stop).then_some((start, stop))
+    }
+
+    /// A number, day or month name of the field, checked against its range.
+    fn item(&mut self, field: Field) -> Option<i32> {
+        let token = self.next()?;
+        let value = match field {
+            Field::Month => month(&token).or_else(|| number(&token)),
+            Field::DayOfWeek => weekday(&token),
+            Field::DayOfMonth => ordinal(&token).or_else(|| number(&token)),
+            _ => number(&token),
+        }?;
+        let (min, max) = bounds(field);
+        (min..=max).contains(&value).then_some(value)
+    }
+
+    /// Items like "1, 2, and 3", with ranges like "5 through 10" among them.
+    fn items(&mut self, field: Field) -> Result<Value, String> {
+        let mut items = Vec::new();
+        loop {
+            let item = self.attempt(|p| {
+                let start = p.item(field)?;
+                let stop = p.attempt(|p| {
+                    p.eat(&["through", "to", "thru", "-"]).then_some(())?;
+                    p.item(field)
+                });
+                Some((start, stop))
+            });
+            match item {
+                Some(item) => items.push(item),
+                None if items.is_empty() => {
+                    return Err(format!(
+                        "expected a {} after '{}'",
+                        field.name().replace('_', "-"),
+                        self.tokens[..self.position].join(" ")
+                    ))
+                }
+                None => break,
+            }
+            // Stop before an "and" that starts the next clause.
+            let more = self.looking_at(|p| {
+                while p.eat(&[",", "and"]) {}
+                p.item(field)
+            });
+            if !more {
+                break;
+            }
+            while self.eat(&[",", "and"]) {}
+        }
+        Ok(list_value(items))
+    }
+
+    fn at(&mut self) -> Result<(), String> {
+        if self.eat(&["every", "each"]) {
+            return self.every();
+        }
+        if self.eat(&["minute", "minutes"]) {
+            let value = self.items(Field::Minute)?;
+            return self.set(Field::Minute, value);
+        }
+        self.times()
+    }
+
+    fn past(&mut self) -> Result<(), String> {
+        if self.eat(&["every", "each"]) {
+            return self.every();
+        }
+        self.eat(&["hour", "hours"]);
+        let value = self.items(Field::Hour)?;
+        self.set(Field::Hour, value)
+    }
+
+    fn on(&mut self) -> Result<(), String> {
+        if self.eat(&["every", "each"]) {
+            return self.every();
+        }
+        if self.eat(&["day-of-month", "day", "days"]) {
+            return self.days_of_month();
+        }
+        if self.eat(&["day-of-week"]) {
+            let value = self.items(Field::DayOfWeek)?;
+            return self.set(Field::DayOfWeek, value);
+        }
+        self.eat(&["the"]);
+        match self.peek() {
+            Some(token) if ordinal(token).is_some() || number(token).is_some() => {
+                self.days_of_month()
+            }
+            _ => self.days_of_week(),
+        }
+    }
+
+    fn days_of_month(&mut self) -> Result<(), String> {
+        let value = self.items(Field::DayOfMonth)?;
+        // "the 1st of every month"
+        self.attempt(|p| {
+            p.eat(&["of"]).then_some(())?;
+            p.eat(&["the", "every", "each"]);
+            p.eat(&["month"]).then_some(())
+        });
+        self.set(Field::DayOfMonth, value)
+    }
+
+    fn days_of_week(&mut self) -> Result<(), String> {
+        if let Some(value) = self.peek().and_then(weekday_group) {
+            self.position += 1;
+            return self.set(Field::DayOfWeek, value);
+        }
+        let value = self.items(Field::DayOfWeek)?;
+        self.set(Field::DayOfWeek, value)
+    }
+
+    fn in_months(&mut self) -> Result<(), String> {
+        if self.eat(&["every", "each"]) {
+            return self.every();
+        }
+        let value = self.items(Field::Month)?;
+        self.set(Field::Month, value)
+    }
+
+    /// A time of day like "9:30", "9:30 am", "17:00", "9" or "noon". Returns
+    /// the hour, the minute and whether "am" or "pm" was given.
+    fn time(&mut self) -> Option<(i32, i32, bool)> {
+        let token = self.next()?;
+        let (hour, minute) = match token.as_str() {
+            "noon" | "midday" => return Some((12, 0, true)),
+            "midnight" => return Some((0, 0, true)),
+            _ => match token.split_once(':') {
+                Some((hour, minute)) => (hour.parse().ok()?, minute.parse().ok()?),
+                None => (token.parse().ok()?, 0),
+            },
+        };
+        let (hour, meridiem) = match self.peek() {
+            Some("am") if (1..=12).contains(&hour) => (hour % 12, true),
+            Some("pm") if (1..=12).contains(&hour) => (hour % 12 + 12, true),
+            _ => (hour, false),
+        };
+        if meridiem {
+            self.position += 1;
+        }
+        self.eat(&["o'clock"]);
+        ((0..24).contains(&hour) && (0..60).contains(&minute)).then_some((hour, minute, meridiem))
+    }
+
+    /// "at 9:30am", "at 09:30 and 17:30" and so on. The times must be every
+    /// combination of their hours and minutes to fit in one schedule.
+    fn times(&mut self) -> Result<(), String> {
+        let mut times = Vec::new();
+        loop {
+            match self.attempt(|p| p.time()) {
+                Some((hour, minute, _)) => times.push((hour, minute)),
+                None if times.is_empty() => {
+                    return Err(format!(
+                        "expected a time of day after '{}'",
+                        self.tokens[..self.position].join(" ")
+                    ))
+                }
+                None => break,
+            }
+            let more = self.looking_at(|p| {
+                while p.eat(&[",", "and"]) {}
+                p.time()
+            });
+            if !more {
+                break;
+            }
+            while self.eat(&[",", "and"]) {}
+        }
+
+        let mut hours = times.iter().map(|(hour, _)| *hour).collect::<Vec<_>>();
+        let mut minutes = times.iter().map(|(_, minute)| *minute).collect::<Vec<_>>();
+        for values in [&mut hours, &mut minutes] {
+            values.sort();
+            values.dedup();
+        }
+        if hours.len() * minutes.len() != times.len() {
+            return Err(
+                "these times need more than one cron line, since not every hour has the same minutes"
+                    .to_string(),
+            );
+        }
+        let value = |values: Vec<i32>| list_value(values.into_iter().map(|v| (v, None)).collect());
+        self.set(Field::Minute, value(minutes))?;
+        self.set(Field::Hour, value(hours))
+    }
+
+    /// "between 9 and 5", "from 9am to 5pm", "from Monday to Friday" or
+    /// "between January and March". Hours end before the last one, unless
+    /// it's said with "through".
+    fn window(&mut self) -> Result<(), String> {
+        let token = self.peek().unwrap_or_default();
+        let field = if weekday(token).is_some() {
+            Some(Field::DayOfWeek)
+        } else if month(token).is_some() {
+            Some(Field::Month)
+        } else {
+            None
+        };
+        if let Some(field) = field {
+            let (start, stop) = self.range(field).ok_or_else(|| {
+                format!("expected a range of {}s", field.name().replace('_', "-"))
+            })?;
+            return self.set(field, Value::Range(start, stop, None));
+        }
+
+        let window = self.attempt(|p| {
+            let start = p.time()?;
+            let through = p.peek() == Some("through");
+            p.eat(&["and", "to", "until", "till", "through", "-"])
+                .then_some(())?;
+            Some((start, p.time()?, through))
+        });
+        let Some(((start, start_minute, _), (stop, stop_minute, meridiem), through)) = window
+        else {
+            return Err(format!(
+                "expected a window like 'between 9 and 5' after '{}'",
+                self.tokens[..self.position].join(" ")
+            ));
+        };
+        if start_minute != 0 || stop_minute != 0 {
+            return Err("cron can only limit runs to whole hours".to_string());
+        }
+        // "between 9 and 5" means until 5 in the afternoon.
+        let stop = if stop < start && !meridiem && stop < 12 {
+            stop + 12
+        } else {
+            stop
+        };
+        let stop = if through { stop } else { stop - 1 };
+        if stop < start {
+            return Err("the window ends before it starts".to_string());
+        }
+        self.set(Field::Hour, Value::Range(start, stop, None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron::{self, DescribeOptions};
+    use crate::datefmt::Clock;
+
+    fn cron_line(input: &str) -> String {
+        parse(input).unwrap().to_string()
+    }
+
+    #[test]
+    fn colloquial_forms() {
+        assert_eq!(cron_line("every weekday at 9:30am"), "30 9 * * 1-5");
+        assert_eq!(
+            cron_line("at midnight on the first of every month"),
+            "0 0 1 * *"
+        );
+        assert_eq!(
+            cron_line("every 15 minutes between 9 and 5 on Mondays"),
+            "*/15 9-16 * * 1"
+        );
+        assert_eq!(cron_line("every 2 hours"), "0 */2 * * *");
+        assert_eq!(cron_line("hourly"), "0 * * * *");
+        assert_eq!(cron_line("daily at noon"), "0 12 * * *");
+        assert_eq!(cron_line("every other day"), "0 0 */2 * *");
+        assert_eq!(cron_line("at 6pm on weekends"), "0 18 * * 0,6");
+        assert_eq!(cron_line("on the 1st and 15th at 8:00"), "0 8 1,15 * *");
+        assert_eq!(
+            cron_line("every Monday and Friday at 5:30 p.m."),
+            "30 17 * * 1,5"
+        );
+        assert_eq!(cron_line("from Monday to Friday at 7am"), "0 7 * * 1-5");
+        assert_eq!(cron_line("at 9am and 5pm in December"), "0 9,17 * 12 *");
+        assert_eq!(cron_line("every minute"), "* * * * *");
+    }
+
+    #[test]
+    fn unclear_forms() {
+        assert!(parse("").is_err());
+        assert!(parse("tomorrow").is_err());
+        assert!(parse("every 2 weeks").is_err());
+        assert!(parse("at 25:00").is_err());
+        assert!(parse("at 9:30 and 10:45").is_err());
+        assert!(parse("at 9:00 at 10:00").is_err());
+        assert_eq!(
+            parse("every 90 minutes").unwrap_err(),
+            "cron can't run every 90 minutes, as steps can be at most 59; try 'explaincron every 90m'"
+        );
+        assert!(parse("every 60 minutes").is_err());
+        assert!(parse("every 40 days").is_err());
+        assert_eq!(
+            parse("every 13 months").unwrap_err(),
+            "cron can't run every 13 months, as steps can be at most 12"
+        );
+        assert!(parse("every 59 minutes").is_ok());
+    }
+
+    /// Reading the description of a schedule gives back the schedule.
+    #[test]
+    fn round_trip() {
+        for schedule in [
+            "* * * * *",
+            "*/5 * * * *",
+            "0 * * * *",
+            "30 9 * * 1-5",
+            "*/15 9-17 * * 1-5",
+            "0 0 1 * *",
+            "5 4 * * SUN",
+            "23 0-20/2 * * *",
+            "3-5 1/4 * FEB SUN",
+            "0 0,12 1 */2 *",
+            "0 4 8-14 * *",
+            "0 9 1 * MON",
+            "0 9 */2 * MON",
+            "0 0 1 1,4,7,10 *",
+            "15 10 * * SAT,SUN",
+            "0 12 * 6-8 *",
+            "0 0 * * 1/2",
+            "10,20 */6 1,15 * *",
+        ] {
+            let schedule = Schedule::from_str(schedule).unwrap().normalize();
+            for (verbose, clock) in [
+                (false, Clock::TwentyFourHour),
+                (false, Clock::TwelveHour),
+                (true, Clock::TwentyFourHour),
+            ] {
+                let options = DescribeOptions {
+                    verbose,
+                    clock,
+                    ..Default::default()
+                };
+                let description = cron::describe(&schedule, &options);
+                assert_eq!(
+                    parse(&description),
+                    Ok(schedule.clone()),
+                    "reading '{description}'"
+                );
+            }
+        }
+    }
+
+    /// Colloquial forms read the same after describing them.
+    #[test]
+    fn stable_descriptions() {
+        for input in [
+            "every weekday at 9:30am",
+            "every 15 minutes between 9 and 5 on Mondays",
+            "at midnight on the first of every month",
+            "every 3 months",
+        ] {
+            let options = DescribeOptions::default();
+            let description = cron::describe(&parse(input).unwrap(), &options);
+            assert_eq!(
+                cron::describe(&parse(&description).unwrap(), &options),
+                description
+            );
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use time::{Duration, OffsetDateTime};
+
+use crate::cron::{self, Schedule};
+
+pub struct Overlaps {
+    /// The shortest time between two consecutive runs in the window.
+    pub min_gap: Option<Duration>,
+    /// The most runs in progress at the same time.
+    pub max_concurrent: usize,
+    /// The first `(earlier, later)` run starts that overlap.
+    pub pairs: Vec<(OffsetDateTime, OffsetDateTime)>,
+}
+
+fn runs(schedule: &Schedule, from_time: OffsetDateTime, window: Duration) -> Vec<OffsetDateTime> {
+    let end = from_time + window;
+    cron::occurrences(from_time, schedule)
+        .take_while(|o| *o < end)
+        .collect()
+}
+
+/// Finds runs of a job that start before its previous run, taking
+/// `runtime`, has finished.
+pub fn self_overlaps(
+    schedule: &Schedule,
+    runtime: Duration,
+    from_time: OffsetDateTime,
+    window: Duration,
+    max_pairs: usize,
+) -> Overlaps {
+    let runs = runs(schedule, from_time, window);
+
+    let min_gap = runs.windows(2).map(|w| w[1] - w[0]).min();
+
+    let mut pairs = Vec::new();
+    let mut max_concurrent = runs.len().min(1);
+    let mut oldest_running = 0;
+    for (i, start) in runs.iter().enumerate() {
+        while oldest_running < i && *start - runs[oldest_running] >= runtime {
+            oldest_running += 1;
+        }
+        max_concurrent = max_concurrent.max(i + 1 - oldest_running);
+        for earlier in &runs[oldest_running..i] {
+            if pairs.len() < max_pairs {
+                pairs.push((*earlier, *start));
+            }
+        }
+    }
+
+    Overlaps {
+        min_gap,
+        max_concurrent,
+        pairs,
+    }
+}
+
+/// Finds runs of two jobs that are in progress at the same time. Returns the
+/// first `(start of a, start of b)` pairs in order of the later start.
+pub fn overlaps_between(
+    a: (&Schedule, Duration),
+    b: (&Schedule, Duration),
+    from_time: OffsetDateTime,
+    window: Duration,
+    max_pairs: usize,
+) -> Vec<(OffsetDateTime, OffsetDateTime)> {
+    let a_runs = runs(a.0, from_time, window);
+    let b_runs = runs(b.0, from_time, window);
+
+    let mut pairs = Vec::new();
+    let mut first_b = 0;
+    for a_start in &a_runs {
+        let a_end = *a_start + a.1;
+        while first_b < b_runs.len() && b_runs[first_b] + b.1 <= *a_start {
+            first_b += 1;
+        }
+        for b_start in b_runs[first_b..].iter().take_while(|b| **b < a_end) {
+            pairs.push((*a_start, *b_start));
+        }
+    }
+    pairs.sort_by_key(|(a_start, b_start)| (*a_start.max(b_start), *a_start.min(b_start)));
+    pairs.truncate(max_pairs);
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn no_self_overlap() {
+        let schedule = Schedule::from_str("0 * * * *").unwrap();
+        let overlaps = self_overlaps(
+            &schedule,
+            Duration::minutes(25),
+            datetime!(2023-03-22 00:00:00 +0:00:00),
+            Duration::days(1),
+            5,
+        );
+
+        assert_eq!(overlaps.min_gap, Some(Duration::hours(1)));
+        assert_eq!(overlaps.max_concurrent, 1);
+        assert!(overlaps.pairs.is_empty());
+
+        let instant = self_overlaps(
+            &schedule,
+            Duration::ZERO,
+            datetime!(2023-03-22 00:00:00 +0:00:00),
+            Duration::days(1),
+            5,
+        );
+        assert!(instant.pairs.is_empty());
+    }
+
+    #[test]
+    fn self_overlap() {
+        let schedule = Schedule::from_str("*/10 1 * * *").unwrap();
+        let overlaps = self_overlaps(
+            &schedule,
+            Duration::minutes(25),
+            datetime!(2023-03-22 00:00:00 +0:00:00),
+            Duration::days(1),
+            3,
+        );
+
+        assert_eq!(overlaps.min_gap, Some(Duration::minutes(10)));
+        assert_eq!(overlaps.max_concurrent, 3);
+        assert_eq!(
+            overlaps.pairs,
+            vec![
+                (
+                    datetime!(2023-03-22 01:00:00 +0:00:00),
+                    datetime!(2023-03-22 01:10:00 +0:00:00)
+                ),
+                (
+                    datetime!(2023-03-22 01:00:00 +0:00:00),
+                    datetime!(2023-03-22 01:20:00 +0:00:00)
+                ),
+                (
+                    datetime!(2023-03-22 01:10:00 +0:00:00),
+                    datetime!(2023-03-22 01:20:00 +0:00:00)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn overlap_between_jobs() {
+        let backup = Schedule::from_str("0 2 * * *").unwrap();
+        let report = Schedule::from_str("30 1,2,3 * * *").unwrap();
+        let pairs = overlaps_between(
+            (&backup, Duration::hours(1)),
+            (&report, Duration::minutes(45)),
+            datetime!(2023-03-22 00:00:00 +0:00:00),
+            Duration::days(1),
+            5,
+        );
+
+        assert_eq!(
+            pairs,
+            vec![
+                (
+                    datetime!(2023-03-22 02:00:00 +0:00:00),
+                    datetime!(2023-03-22 01:30:00 +0:00:00)
+                ),
+                (
+                    datetime!(2023-03-22 02:00:00 +0:00:00),
+                    datetime!(2023-03-22 02:30:00 +0:00:00)
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,478 @@
+//! Organization rules that schedules are checked against, read from a TOML
+//! file of `[[rule]]` tables:
+//!
+//! ```toml
+//! [[rule]]
+//! name = "no daytime jobs on DB hosts"
+//! files = ["*db*"]
+//! forbid = "* 8-17 * * 1-5"
+//!
+//! [[rule]]
+//! name = "at most every 10 minutes"
+//! min_interval = "10m"
+//! severity = "warning"
+//! ```
+
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+
+use time::{Duration, OffsetDateTime};
+
+use crate::check;
+use crate::cron::{self, Schedule};
+use crate::duration;
+use crate::lint::Severity;
+use crate::schedule_set::ScheduleSet;
+use crate::statistics;
+
+pub struct Rule {
+    pub name: String,
+    /// Patterns of the crontab paths the rule applies to, or empty for all.
+    pub files: Vec<String>,
+    pub severity: Severity,
+    /// Times no job may run at, as schedules joined like for `combine`.
+    pub forbid: Option<ScheduleSet>,
+    /// The shortest time allowed between two runs of a job.
+    pub min_interval: Option<Duration>,
+    /// The most runs of a job allowed on one day.
+    pub max_per_day: Option<usize>,
+}
+
+pub struct Policy {
+    pub rules: Vec<Rule>,
+}
+
+/// A schedule breaking a rule.
+pub struct Violation<'a> {
+    pub rule: &'a Rule,
+    /// What's wrong, like "runs at a forbidden time".
+    pub problem: String,
+    /// The first run that breaks the rule.
+    pub first: OffsetDateTime,
+}
+
+impl Policy {
+    pub fn from_str(input: &str) -> Result<Policy, String> {
+        let rules = parse_tables(input)?
+            .into_iter()
+            .map(|(line, table)| parse_rule(table).map_err(|e| format!("rule on line {line}: {e}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Policy { rules })
+    }
+
+    /// The rules that the schedule of a job in the crontab at `path` breaks,
+    /// with the first offending run at or after `from_time`.
+    pub fn evaluate(
+        &self,
+        path: &str,
+        schedule: &Schedule,
+        from_time: OffsetDateTime,
+    ) -> Vec<Violation<'_>> {
+        let last_year = from_time.year() + cron::MAX_SEARCH_YEARS;
+        let runs =
+            || cron::occurrences(from_time, schedule).take_while(|run| run.year() <= last_year);
+        // The statistics tell whether the rules are broken at all, which
+        // saves walking through years of runs when they aren't.
+        let statistics = OnceCell::new();
+        let mut violations = Vec::new();
+
+        for rule in self.rules.iter().filter(|rule| {
+            rule.files.is_empty()
+                || rule
+                    .files
+                    .iter()
+                    .any(|pattern| check::glob_matches(pattern, path))
+        }) {
+            let mut violation = |problem: String, first: Option<OffsetDateTime>| {
+                if let Some(first) = first {
+                    violations.push(Violation {
+                        rule,
+                        problem,
+                        first,
+                    });
+                }
+            };
+
+            if let Some(forbid) = &rule.forbid {
+                let both = ScheduleSet::Schedule(schedule.clone()).intersect(forbid.clone());
+                violation(
+                    "runs at a forbidden time".to_string(),
+                    both.next_occurrence(from_time).ok(),
+                );
+            }
+
+            if rule.min_interval.is_none() && rule.max_per_day.is_none() {
+                continue;
+            }
+            let Some(statistics) = statistics.get_or_init(|| statistics::statistics(schedule))
+            else {
+                continue;
+            };
+            if let Some(min_interval) = rule.min_interval.filter(|i| statistics.min_gap < *i) {
+                let mut previous = None;
+                let first = runs().find(|run| {
+                    let too_soon = previous.is_some_and(|p| *run - p < min_interval);
+                    previous = Some(*run);
+                    too_soon
+                });
+                violation(
+                    format!(
+                        "runs only {} after the previous run",
+                        duration::format(statistics.min_gap)
+                    ),
+                    first,
+                );
+            }
+            if let Some(max) = rule.max_per_day.filter(|max| statistics.per_day.max > *max) {
+                let mut per_day = BTreeMap::new();
+                let first = runs().find(|run| {
+                    let count = per_day.entry(run.date()).or_insert(0);
+                    *count += 1;
+                    *count > max
+                });
+                violation(
+                    format!("runs {} times on some days", statistics.per_day.max),
+                    first,
+                );
+            }
+        }
+
+        violations
+    }
+}
+
+fn parse_rule(table: Table) -> Result<Rule, String> {
+    let mut rule = Rule {
+        name: String::new(),
+        files: Vec::new(),
+        severity: Severity::Error,
+        forbid: None,
+        min_interval: None,
+        max_per_day: None,
+    };
+    for (line, key, value) in table {
+        let at = |e: String| format!("line {line}: {e}");
+        match (key.as_str(), value) {
+            ("name", Toml::String(name)) => rule.name = name,
+            ("files", Toml::Array(files)) => {
+                rule.files = files
+                    .into_iter()
+                    .map(|file| match file {
+                        Toml::String(file) => Ok(file),
+                        _ => Err(at("'files' must be a list of strings".to_string())),
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+            ("severity", Toml::String(severity)) => {
+                rule.severity = match severity.as_str() {
+                    "note" => Severity::Note,
+                    "warning" => Severity::Warning,
+                    "error" => Severity::Error,
+                    _ => {
+                        return Err(at(format!(
+                            "'{severity}' is not a severity (note, warning or error)"
+                        )))
+                    }
+                }
+            }
+            ("forbid", Toml::String(forbid)) => {
+                rule.forbid = Some(ScheduleSet::from_str(&forbid).map_err(at)?)
+            }
+            ("min_interval", Toml::String(interval)) => {
+                rule.min_interval = Some(duration::parse(&interval).map_err(at)?)
+            }
+            ("max_per_day", Toml::Integer(max)) if max >= 0 => {
+                rule.max_per_day = Some(max as usize)
+            }
+            (
+                key @ ("name" | "files" | "severity" | "forbid" | "min_interval" | "max_per_day"),
+                _,
+            ) => return Err(at(format!("'{key}' has the wrong type"))),
+            (key, _) => return Err(at(format!("unknown key '{key}'"))),
+        }
+    }
+    if rule.name.is_empty() {
+        return Err("every rule needs a name".to_string());
+    }
+    if rule.forbid.is_none() && rule.min_interval.is_none() && rule.max_per_day.is_none() {
+        return Err(format!(
+            "'{}' needs forbid, min_interval or max_per_day",
+            rule.name
+        ));
+    }
+    Ok(rule)
+}
+
+/// The values of the small part of TOML a policy needs.
+#[derive(Debug, PartialEq)]
+enum Toml {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<Toml>),
+}
+
+/// The keys and values of a TOML table, each with the line it's on.
+type Table = Vec<(usize, String, Toml)>;
+
+/// The `[[rule]]` tables of a TOML document, each with the line it starts
+/// on. Supports strings on one line, integers, booleans, arrays and
+/// comments, but not dotted keys or inline tables.
+fn parse_tables(input: &str) -> Result<Vec<(usize, Table)>, String> {
+    let mut tables = Vec::new();
+    let mut lines = input.lines().enumerate().map(|(i, line)| (i + 1, line));
+    while let Some((line_number, line)) = lines.next() {
+        let at = |e: &str| format!("line {line_number}: {e}");
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            let header = line.split('#').next().unwrap().trim();
+            if header != "[[rule]]" {
+                return Err(at(&format!(
+                    "unknown table {header}, only [[rule]] is allowed"
+                )));
+            }
+            tables.push((line_number, Vec::new()));
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| at("expected a key = value pair"))?;
+        let key = key.trim();
+        if key.contains('.') {
+            return Err(at(&format!(
+                "'{key}' is a dotted key, which isn't supported"
+            )));
+        }
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(at(&format!("'{key}' is not a valid key")));
+        }
+        let (value, rest) = parse_value(value.trim_start(), &mut lines).map_err(|e| at(&e))?;
+        let rest = rest.trim_start();
+        if !rest.is_empty() && !rest.starts_with('#') {
+            return Err(at(&format!("unexpected '{rest}' after the value")));
+        }
+        let (_, table) = tables
+            .last_mut()
+            .ok_or_else(|| at("keys must be inside a [[rule]] table"))?;
+        if table.iter().any(|(_, other, _)| other == key) {
+            return Err(at(&format!("'{key}' is set twice")));
+        }
+        table.push((line_number, key.to_string(), value));
+    }
+    Ok(tables)
+}
+
+/// A value at the start of `input`, and the rest of its line. Arrays may go
+/// on over the following `lines`.
+fn parse_value<'a>(
+    input: &'a str,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<(Toml, &'a str), String> {
+    if input.starts_with("\"\"\"") || input.starts_with("'''") {
+        Err("multi-line strings aren't supported".to_string())
+    } else if input.starts_with('{') {
+        Err("inline tables aren't supported".to_string())
+    } else if let Some(rest) = input.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Ok((Toml::String(value), &rest[i + 1..])),
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    _ => return Err("unsupported escape in string".to_string()),
+                },
+                c => value.push(c),
+            }
+        }
+        Err("unterminated string".to_string())
+    } else if let Some(rest) = input.strip_prefix('\'') {
+        let (value, rest) = rest
+            .split_once('\'')
+            .ok_or_else(|| "unterminated string".to_string())?;
+        Ok((Toml::String(value.to_string()), rest))
+    } else if let Some(mut rest) = input.strip_prefix('[') {
+        let mut values = Vec::new();
+        loop {
+            rest = skip_blank(rest, lines)?;
+            if let Some(after) = rest.strip_prefix(']') {
+                return Ok((Toml::Array(values), after));
+            }
+            let (value, after) = parse_value(rest, lines)?;
+            values.push(value);
+            rest = skip_blank(after, lines)?;
+            if let Some(after) = rest.strip_prefix(',') {
+                rest = after;
+            } else if !rest.starts_with(']') {
+                return Err("expected ',' or ']' in array".to_string());
+            }
+        }
+    } else {
+        let end = input
+            .find(|c: char| c.is_whitespace() || c == ',' || c == ']' || c == '#')
+            .unwrap_or(input.len());
+        let (word, rest) = input.split_at(end);
+        let value = match word {
+            "true" => Toml::Boolean(true),
+            "false" => Toml::Boolean(false),
+            _ => Toml::Integer(
+                word.replace('_', "")
+                    .parse()
+                    .map_err(|_| format!("'{word}' is not a string, number, boolean or array"))?,
+            ),
+        };
+        Ok((value, rest))
+    }
+}
+
+/// The rest of an array from `rest`, skipping whitespace, comments and the
+/// ends of lines.
+fn skip_blank<'a>(
+    mut rest: &'a str,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<&'a str, String> {
+    loop {
+        rest = rest.trim_start();
+        if !rest.is_empty() && !rest.starts_with('#') {
+            return Ok(rest);
+        }
+        let (_, line) = lines
+            .next()
+            .ok_or_else(|| "unterminated array".to_string())?;
+        rest = line;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    const POLICY: &str = r#"
+# Database hosts are busy during office hours.
+[[rule]]
+name = "no daytime jobs on DB hosts"
+files = [
+    "*db*",  # crontabs of database hosts
+    '*sql*',
+]
+forbid = "* 8-17 * * 1-5"
+
+[[rule]]
+name = 'at most every 10 minutes'
+min_interval = "10m"
+severity = "warning"
+
+[[rule]]
+name = "not at minute 0"
+forbid = "0 * * * *"
+
+[[rule]]
+name = "hourly at most"
+max_per_day = 24
+"#;
+
+    fn violations(path: &str, schedule: &str) -> Vec<(String, String, OffsetDateTime)> {
+        Policy::from_str(POLICY)
+            .unwrap()
+            .evaluate(
+                path,
+                &Schedule::from_str(schedule).unwrap(),
+                datetime!(2026-10-17 12:00:00 +0:00:00),
+            )
+            .into_iter()
+            .map(|v| (v.rule.name.clone(), v.problem, v.first))
+            .collect()
+    }
+
+    #[test]
+    fn evaluates_rules() {
+        assert!(violations("/etc/cron.d/db-backup", "30 2 * * *").is_empty());
+        assert_eq!(
+            violations("/etc/cron.d/db-backup", "30 9 * * *"),
+            vec![(
+                "no daytime jobs on DB hosts".to_string(),
+                "runs at a forbidden time".to_string(),
+                datetime!(2026-10-19 09:30:00 +0:00:00)
+            )]
+        );
+        assert!(violations("/etc/cron.d/web", "30 9 * * *").is_empty());
+        assert_eq!(
+            violations("/etc/cron.d/web", "*/7 * * * *"),
+            vec![
+                (
+                    "at most every 10 minutes".to_string(),
+                    "runs only 4m after the previous run".to_string(),
+                    datetime!(2026-10-17 12:07:00 +0:00:00)
+                ),
+                (
+                    "not at minute 0".to_string(),
+                    "runs at a forbidden time".to_string(),
+                    datetime!(2026-10-17 12:00:00 +0:00:00)
+                ),
+                (
+                    "hourly at most".to_string(),
+                    "runs 216 times on some days".to_string(),
+                    datetime!(2026-10-17 14:42:00 +0:00:00)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_policies() {
+        let error = |policy| Policy::from_str(policy).err().unwrap();
+
+        assert_eq!(
+            error("name = \"x\""),
+            "line 1: keys must be inside a [[rule]] table"
+        );
+        assert_eq!(
+            error("[[rule]]\nname = \"x\"\nforbid = \"61 * * * *\""),
+            "rule on line 1: line 3: '61' is not a valid minute (0-59)"
+        );
+        assert_eq!(
+            error("[[rule]]\nname = \"x\""),
+            "rule on line 1: 'x' needs forbid, min_interval or max_per_day"
+        );
+        assert_eq!(
+            error("[[rule]]\nname = \"x\"\nmax_per_day = \"2\""),
+            "rule on line 1: line 3: 'max_per_day' has the wrong type"
+        );
+        assert_eq!(
+            error("[[rule]]\nname = \"x\"\nmin_interval = \"9999999999999999w\""),
+            "rule on line 1: line 3: '9999999999999999w' is too long a duration"
+        );
+        assert_eq!(
+            error("[[rule]]\nfiles = [\"a\",\n"),
+            "line 2: unterminated array"
+        );
+        assert_eq!(
+            error("[[rule]]\nlimits.max_per_day = 2"),
+            "line 2: 'limits.max_per_day' is a dotted key, which isn't supported"
+        );
+        assert_eq!(
+            error("[[rule]]\nlimits = { max_per_day = 2 }"),
+            "line 2: inline tables aren't supported"
+        );
+        assert_eq!(
+            error("[[rule]]\nname = \"\"\"x\"\"\""),
+            "line 2: multi-line strings aren't supported"
+        );
+        assert_eq!(
+            error("[rules]"),
+            "line 1: unknown table [rules], only [[rule]] is allowed"
+        );
+    }
+}
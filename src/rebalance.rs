@@ -0,0 +1,318 @@
+use time::{Duration, OffsetDateTime};
+
+use crate::cron::{self, Hour, Minute, Schedule, Value};
+use crate::crontab::Entry;
+
+// How many minutes on each side of a start still count as crowded. Closer
+// starts weigh quadratically more.
+const SPREAD: i64 = 30;
+
+struct Candidate {
+    cost: i64,
+    distance: (i32, i32),
+    hour_shift: i32,
+    minute_shift: i32,
+    indices: Vec<i64>,
+}
+
+struct Constraints {
+    keep: bool,
+    hours: (i32, i32),
+    minutes: (i32, i32),
+}
+
+fn constraints(entry: &Entry) -> Result<Constraints, String> {
+    let err = |e: String| format!("line {}: {e}", entry.line);
+    let hours = match (entry.annotation("hours"), &entry.schedule.hour.value) {
+        (Some(range), Value::Single(_)) => {
+            parse_range(range, Hour::from_str, |h| h.value).map_err(err)?
+        }
+        (Some(_), _) => {
+            return Err(err(
+                "'hours' only applies to jobs that run at a single hour".to_string(),
+            ))
+        }
+        (None, Value::Single(hour)) => ((hour - 1).max(0), (hour + 1).min(23)),
+        (None, _) => (0, 23),
+    };
+    let minutes = match entry.annotation("minutes") {
+        Some(range) => parse_range(range, Minute::from_str, |m| m.value).map_err(err)?,
+        None => (0, 59),
+    };
+
+    Ok(Constraints {
+        keep: entry.annotation("keep").is_some(),
+        hours,
+        minutes,
+    })
+}
+
+fn parse_range<T>(
+    input: &str,
+    parser: fn(&str) -> Result<T, String>,
+    value: fn(T) -> Value,
+) -> Result<(i32, i32), String> {
+    match value(parser(input)?) {
+        Value::Single(single) => Ok((single, single)),
+        Value::Range(start, stop, None) => Ok((start, stop)),
+        _ => Err(format!("'{input}' must be a number or a range like 1-3")),
+    }
+}
+
+/// Suggests new start minutes (and, for jobs at a single hour, start hours
+/// within the allowed range) that spread the entries' runs over `window`.
+/// The window is treated as repeating, so it should be a multiple of the
+/// jobs' periods, like the default week. Returns the new schedule of every
+/// entry that should move.
+pub fn rebalance(
+    entries: &[Entry],
+    from_time: OffsetDateTime,
+    window: Duration,
+) -> Result<Vec<Option<Schedule>>, String> {
+    let start = from_time
+        .replace_second(0)
+        .and_then(|d| d.replace_nanosecond(0))
+        .unwrap();
+    if window < Duration::MINUTE {
+        return Err("the window must be at least one minute".to_string());
+    }
+    let end = start + window;
+    let length = window.whole_minutes() as usize;
+    let mut pressure = vec![0i64; length];
+
+    let runs = entries
+        .iter()
+        .map(|entry| {
+            cron::occurrences(start, &entry.schedule)
+                .take_while(|o| *o < end)
+                .map(|o| ((o - start).whole_minutes(), o.minute() as i32))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut movable = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let constraints = constraints(entry)?;
+        let minutes = entry.schedule.minute.value.values(0, 59);
+        if constraints.keep || minutes.len() == 60 || runs[i].is_empty() {
+            add_pressure(&mut pressure, runs[i].iter().map(|(index, _)| *index));
+        } else {
+            movable.push((i, constraints, minutes));
+        }
+    }
+    movable.sort_by_key(|(i, _, _)| std::cmp::Reverse(runs[*i].len()));
+
+    let mut result = vec![None; entries.len()];
+    for (i, constraints, minutes) in movable {
+        let hour = match entries[i].schedule.hour.value {
+            Value::Single(hour) => Some(hour),
+            _ => None,
+        };
+        let hour_shifts = match hour {
+            Some(hour) => (constraints.hours.0 - hour)..=(constraints.hours.1 - hour),
+            None => 0..=0,
+        };
+
+        let mut best: Option<Candidate> = None;
+        for hour_shift in hour_shifts {
+            for minute_shift in 0..60 {
+                let (first, last) = constraints.minutes;
+                if minutes
+                    .iter()
+                    .any(|m| !(first..=last).contains(&((m + minute_shift) % 60)))
+                {
+                    continue;
+                }
+                let indices = runs[i]
+                    .iter()
+                    .map(|(index, m)| {
+                        (index - *m as i64
+                            + ((m + minute_shift) % 60) as i64
+                            + hour_shift as i64 * 60)
+                            .rem_euclid(length as i64)
+                    })
+                    .collect::<Vec<_>>();
+                let cost = indices.iter().map(|index| pressure[*index as usize]).sum();
+                // Prefer the smallest change among equally good start times.
+                let distance = (hour_shift.abs(), minute_shift);
+                if best
+                    .as_ref()
+                    .is_none_or(|b| (cost, distance) < (b.cost, b.distance))
+                {
+                    best = Some(Candidate {
+                        cost,
+                        distance,
+                        hour_shift,
+                        minute_shift,
+                        indices,
+                    });
+                }
+            }
+        }
+
+        let Some(Candidate {
+            hour_shift,
+            minute_shift,
+            indices,
+            ..
+        }) = best
+        else {
+            return Err(format!(
+                "line {}: no start time satisfies the constraints",
+                entries[i].line
+            ));
+        };
+        add_pressure(&mut pressure, indices.iter().copied());
+
+        if (hour_shift, minute_shift) != (0, 0) {
+            let mut schedule = entries[i].schedule.clone();
+            let mut shifted = minutes
+                .iter()
+                .map(|m| (m + minute_shift) % 60)
+                .collect::<Vec<_>>();
+            shifted.sort();
+            schedule.minute.value = Value::from_values(&shifted, 0, 59);
+            if let Some(hour) = hour {
+                schedule.hour.value = Value::Single(hour + hour_shift);
+            }
+            result[i] = Some(schedule);
+        }
+    }
+
+    Ok(result)
+}
+
+fn add_pressure(pressure: &mut [i64], indices: impl Iterator<Item = i64>) {
+    let length = pressure.len() as i64;
+    for index in indices {
+        for offset in -SPREAD..=SPREAD {
+            pressure[(index + offset).rem_euclid(length) as usize] +=
+                (SPREAD + 1 - offset.abs()).pow(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crontab;
+    use time::macros::datetime;
+
+    fn suggestions(input: &str) -> Vec<Option<String>> {
+        let entries = crontab::parse(input).unwrap();
+        rebalance(
+            &entries,
+            datetime!(2023-03-20 00:00:00 +0:00:00),
+            Duration::days(7),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|s| s.map(|s| s.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn spreads_hourly_jobs() {
+        assert_eq!(
+            suggestions("0 * * * * a\n0 * * * * b\n0 * * * * c"),
+            vec![
+                None,
+                Some("30 * * * *".to_string()),
+                Some("15 * * * *".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_frequency() {
+        assert_eq!(
+            suggestions("*/15 * * * * a\n0,15,30,45 * * * * b"),
+            vec![None, Some("7/15 * * * *".to_string())]
+        );
+    }
+
+    #[test]
+    fn honours_constraints() {
+        let input = "* * * * * busy
+# explaincron: keep
+0 2 * * * fixed
+# explaincron: hours 2
+0 2 * * * pinned
+0 2 * * * free
+# explaincron: minutes 0-4
+0 2 * * * early";
+        let result = suggestions(input);
+
+        assert_eq!(result[0], None);
+        assert_eq!(result[1], None);
+        assert!(result[2].as_ref().unwrap().ends_with(" 2 * * *"));
+        assert!(result[3].is_some());
+        let early = result[4].as_ref().unwrap();
+        assert!(["1", "2", "3", "4"].contains(&early.split(' ').next().unwrap()));
+    }
+
+    #[test]
+    fn suggestions_parse() {
+        let input = "0 2 * * * a\n0 2 * * SUN b\n0 2 * * MON-SUN c\n0 2 1 JAN 0,SAT d";
+        let entries = crontab::parse(input).unwrap();
+        let suggestions = rebalance(
+            &entries,
+            datetime!(2023-03-20 00:00:00 +0:00:00),
+            Duration::days(7),
+        )
+        .unwrap();
+        let output = input
+            .lines()
+            .zip(&entries)
+            .zip(&suggestions)
+            .map(|((line, entry), suggestion)| match suggestion {
+                Some(schedule) => entry.with_schedule(line, schedule),
+                None => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert!(suggestions[1..3].iter().all(Option::is_some), "{output}");
+        let parsed = crontab::parse(&output).unwrap();
+        for ((entry, suggestion), parsed) in entries.iter().zip(&suggestions).zip(&parsed) {
+            assert_eq!(
+                suggestion.as_ref().unwrap_or(&entry.schedule),
+                &parsed.schedule
+            );
+            assert_eq!(entry.command, parsed.command);
+        }
+    }
+
+    #[test]
+    fn impossible_constraints() {
+        let entries = crontab::parse("# explaincron: minutes 50-55\n*/30 * * * * a").unwrap();
+        assert_eq!(
+            rebalance(
+                &entries,
+                datetime!(2023-03-20 00:00:00 +0:00:00),
+                Duration::days(1)
+            )
+            .err()
+            .unwrap(),
+            "line 2: no start time satisfies the constraints"
+        );
+
+        let entries = crontab::parse("# explaincron: hours 2\n0 */6 * * * a").unwrap();
+        assert_eq!(
+            rebalance(
+                &entries,
+                datetime!(2023-03-20 00:00:00 +0:00:00),
+                Duration::days(1)
+            )
+            .err()
+            .unwrap(),
+            "line 2: 'hours' only applies to jobs that run at a single hour"
+        );
+        assert!(rebalance(
+            &crontab::parse("* * * * * a").unwrap(),
+            datetime!(2023-03-20 00:00:00 +0:00:00),
+            Duration::seconds(30)
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,566 @@
+use time::ext::NumericalDuration;
+use time::OffsetDateTime;
+
+use crate::cron::{
+    self, DayOfMonth, DayOfWeek, DescribeOptions, Hour, Minute, Month, Schedule, Value,
+};
+
+/// Schedules combined with set operations, for times a single cron line
+/// can't express, like every 90 minutes (two lines) or every hour except
+/// 02:00.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduleSet {
+    Schedule(Schedule),
+    /// Runs when any of the sets runs.
+    Union(Vec<ScheduleSet>),
+    /// Runs when all of the sets run.
+    Intersection(Vec<ScheduleSet>),
+    /// Runs when the first set runs and the second doesn't.
+    Difference(Box<ScheduleSet>, Box<ScheduleSet>),
+}
+
+impl ScheduleSet {
+    /// Parses schedules joined by `or`, `and` and `except`, applied from left
+    /// to right, like `0 * * * * except 0 2 * * *`.
+    pub fn from_str(s: &str) -> Result<ScheduleSet, String> {
+        let mut terms = Vec::new();
+        let mut operators = Vec::new();
+        let mut term = Vec::new();
+        for word in s.split_whitespace() {
+            match word {
+                "or" | "and" | "except" => {
+                    terms.push(term.join(" "));
+                    operators.push(word);
+                    term.clear();
+                }
+                _ => term.push(word),
+            }
+        }
+        terms.push(term.join(" "));
+
+        let mut terms = terms
+            .iter()
+            .map(|term| Schedule::from_str(term).map(ScheduleSet::Schedule));
+        let mut result = terms.next().unwrap()?;
+        for (operator, term) in operators.into_iter().zip(terms) {
+            result = match operator {
+                "or" => result.union(term?),
+                "and" => result.intersect(term?),
+                _ => result.except(term?),
+            };
+        }
+        Ok(result)
+    }
+
+    pub fn union(self, other: ScheduleSet) -> ScheduleSet {
+        match self {
+            ScheduleSet::Union(mut sets) => {
+                sets.push(other);
+                ScheduleSet::Union(sets)
+            }
+            set => ScheduleSet::Union(vec![set, other]),
+        }
+    }
+
+    pub fn intersect(self, other: ScheduleSet) -> ScheduleSet {
+        match self {
+            ScheduleSet::Intersection(mut sets) => {
+                sets.push(other);
+                ScheduleSet::Intersection(sets)
+            }
+            set => ScheduleSet::Intersection(vec![set, other]),
+        }
+    }
+
+    pub fn except(self, other: ScheduleSet) -> ScheduleSet {
+        ScheduleSet::Difference(Box::new(self), Box::new(other))
+    }
+
+    /// Whether the set runs during the minute of `datetime`.
+    pub fn matches(&self, datetime: OffsetDateTime) -> bool {
+        match self {
+            ScheduleSet::Schedule(schedule) => schedule.matches(datetime),
+            ScheduleSet::Union(sets) => sets.iter().any(|set| set.matches(datetime)),
+            ScheduleSet::Intersection(sets) => sets.iter().all(|set| set.matches(datetime)),
+            ScheduleSet::Difference(set, except) => {
+                set.matches(datetime) && !except.matches(datetime)
+            }
+        }
+    }
+
+    pub fn next_occurrence(&self, from_time: OffsetDateTime) -> Result<OffsetDateTime, String> {
+        let last_year = from_time.year() + cron::MAX_SEARCH_YEARS;
+        self.find_next(from_time, last_year)
+            .ok_or_else(|| format!("'{self}' has no occurrence before {last_year}"))
+    }
+
+    fn find_next(&self, from_time: OffsetDateTime, last_year: i32) -> Option<OffsetDateTime> {
+        match self {
+            ScheduleSet::Schedule(schedule) => cron::next_occurrence(from_time, schedule)
+                .ok()
+                .filter(|next| next.year() <= last_year),
+            ScheduleSet::Union(sets) => sets
+                .iter()
+                .filter_map(|set| set.find_next(from_time, last_year))
+                .min(),
+            ScheduleSet::Intersection(sets) => {
+                let mut from_time = from_time;
+                loop {
+                    let nexts = sets
+                        .iter()
+                        .map(|set| set.find_next(from_time, last_year))
+                        .collect::<Option<Vec<_>>>()?;
+                    let latest = *nexts.iter().max()?;
+                    if nexts.iter().all(|next| *next == latest) {
+                        return Some(latest);
+                    }
+                    from_time = latest;
+                }
+            }
+            // Walks through the runs of the first set, which is slow if the
+            // second one leaves out most of them. `occurrences` avoids this
+            // when the set can be written as cron lines.
+            ScheduleSet::Difference(set, except) => {
+                let mut from_time = from_time;
+                loop {
+                    let next = set.find_next(from_time, last_year)?;
+                    if !except.matches(next) {
+                        return Some(next);
+                    }
+                    from_time = next.saturating_add(1.minutes());
+                }
+            }
+        }
+    }
+
+    /// The runs at or after `from_time`, ending early if the set stops
+    /// running.
+    pub fn occurrences(&self, from_time: OffsetDateTime) -> impl Iterator<Item = OffsetDateTime> {
+        let set = self.simplify();
+        let mut next = Some(from_time);
+        std::iter::from_fn(move || {
+            let occurrence = set.next_occurrence(next?).ok();
+            next = occurrence.map(|o| o.saturating_add(1.seconds()));
+            occurrence
+        })
+    }
+
+    /// A best-effort description, combining those of the schedules.
+    pub fn describe(&self, options: &DescribeOptions) -> String {
+        cron::capitalize(&self.phrase(options))
+    }
+
+    fn phrase(&self, options: &DescribeOptions) -> String {
+        let language = options.language;
+        match self {
+            ScheduleSet::Schedule(schedule) => cron::phrase(&cron::fragments(schedule, options)),
+            ScheduleSet::Union(sets) => language.join(
+                &sets
+                    .iter()
+                    .map(|set| set.phrase(options))
+                    .collect::<Vec<_>>(),
+            ),
+            ScheduleSet::Intersection(sets) => sets
+                .iter()
+                .map(|set| set.phrase(options))
+                .collect::<Vec<_>>()
+                .join(language.set_connector(false)),
+            ScheduleSet::Difference(set, except) => format!(
+                "{}{}{}",
+                set.phrase(options),
+                language.set_connector(true),
+                except.phrase(options)
+            ),
+        }
+    }
+
+    /// An equivalent set that is as simple as possible: a single schedule
+    /// or a union of the fewest schedules found, if the set can be written
+    /// as cron lines at all.
+    pub fn simplify(&self) -> ScheduleSet {
+        match self.to_cron_lines() {
+            Some(mut lines) if lines.len() == 1 => ScheduleSet::Schedule(lines.remove(0)),
+            Some(lines) => {
+                ScheduleSet::Union(lines.into_iter().map(ScheduleSet::Schedule).collect())
+            }
+            None => match self {
+                ScheduleSet::Schedule(schedule) => ScheduleSet::Schedule(schedule.normalize()),
+                ScheduleSet::Union(sets) => {
+                    ScheduleSet::Union(sets.iter().map(Self::simplify).collect())
+                }
+                ScheduleSet::Intersection(sets) => {
+                    ScheduleSet::Intersection(sets.iter().map(Self::simplify).collect())
+                }
+                ScheduleSet::Difference(set, except) => {
+                    ScheduleSet::Difference(Box::new(set.simplify()), Box::new(except.simplify()))
+                }
+            },
+        }
+    }
+
+    /// The set as standard cron lines, or `None` if it can't be written as
+    /// such, like the 1st of the month only if it's a Monday. No lines means
+    /// the set never runs.
+    pub fn to_cron_lines(&self) -> Option<Vec<Schedule>> {
+        to_schedules(self.products())
+    }
+
+    fn products(&self) -> Vec<Product> {
+        match self {
+            ScheduleSet::Schedule(schedule) => schedule_products(schedule),
+            ScheduleSet::Union(sets) => reduce(sets.iter().flat_map(Self::products).collect()),
+            ScheduleSet::Intersection(sets) => sets
+                .iter()
+                .map(Self::products)
+                .reduce(|a, b| {
+                    reduce(
+                        a.iter()
+                            .flat_map(|p| b.iter().filter_map(|q| intersect(p, q)))
+                            .collect(),
+                    )
+                })
+                .unwrap_or_default(),
+            ScheduleSet::Difference(set, except) => {
+                except.products().iter().fold(set.products(), |pieces, q| {
+                    reduce(pieces.iter().flat_map(|p| subtract(p, q)).collect())
+                })
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ScheduleSet {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // Nested sets are bracketed, even though `from_str` doesn't read that.
+        let operand = |set: &ScheduleSet| match set {
+            ScheduleSet::Schedule(schedule) => schedule.to_string(),
+            set => format!("({set})"),
+        };
+        let joined = |sets: &[ScheduleSet], operator| {
+            sets.iter().map(operand).collect::<Vec<_>>().join(operator)
+        };
+        match self {
+            ScheduleSet::Schedule(schedule) => write!(fmt, "{schedule}"),
+            ScheduleSet::Union(sets) => fmt.write_str(&joined(sets, " or ")),
+            ScheduleSet::Intersection(sets) => fmt.write_str(&joined(sets, " and ")),
+            ScheduleSet::Difference(set, except) => {
+                write!(fmt, "{} except {}", operand(set), operand(except))
+            }
+        }
+    }
+}
+
+/// The sorted numbers a schedule allows in each field, in the order minute,
+/// hour, day-of-month, month and day-of-week. A schedule runs at every
+/// combination of them.
+type Product = [Vec<i32>; 5];
+
+const BOUNDS: [(i32, i32); 5] = [(0, 59), (0, 23), (1, 31), (1, 12), (0, 6)];
+const DAY_OF_MONTH: usize = 2;
+const DAY_OF_WEEK: usize = 4;
+
+fn full(field: usize) -> Vec<i32> {
+    (BOUNDS[field].0..=BOUNDS[field].1).collect()
+}
+
+fn is_full(product: &Product, field: usize) -> bool {
+    product[field].len() == full(field).len()
+}
+
+fn schedule_products(schedule: &Schedule) -> Vec<Product> {
+    // Sunday is both 0 and 7 (SUN).
+    let mut day_of_week = schedule
+        .day_of_week
+        .value
+        .values(0, 7)
+        .into_iter()
+        .map(|day| day % 7)
+        .collect::<Vec<_>>();
+    day_of_week.sort();
+    day_of_week.dedup();
+    let product = [
+        schedule.minute.value.values(0, 59),
+        schedule.hour.value.values(0, 23),
+        schedule.day_of_month.value.values(1, 31),
+        schedule.month.value.values(1, 12),
+        day_of_week,
+    ];
+
+    // Restricting both days runs on either of them.
+    if schedule.runs_on_either_day() {
+        let mut by_day_of_month = product.clone();
+        by_day_of_month[DAY_OF_WEEK] = full(DAY_OF_WEEK);
+        let mut by_day_of_week = product;
+        by_day_of_week[DAY_OF_MONTH] = full(DAY_OF_MONTH);
+        vec![by_day_of_month, by_day_of_week]
+    } else {
+        vec![product]
+    }
+}
+
+fn intersect(p: &Product, q: &Product) -> Option<Product> {
+    let mut result = p.clone();
+    for (field, values) in result.iter_mut().enumerate() {
+        values.retain(|v| q[field].contains(v));
+        if values.is_empty() {
+            return None;
+        }
+    }
+    Some(result)
+}
+
+/// `p` without the times in `q`, as products that don't overlap.
+fn subtract(p: &Product, q: &Product) -> Vec<Product> {
+    if intersect(p, q).is_none() {
+        return vec![p.clone()];
+    }
+    let mut pieces = Vec::new();
+    let mut prefix = p.clone();
+    for field in 0..5 {
+        let rest = p[field]
+            .iter()
+            .copied()
+            .filter(|v| !q[field].contains(v))
+            .collect::<Vec<_>>();
+        if !rest.is_empty() {
+            let mut piece = prefix.clone();
+            piece[field] = rest;
+            pieces.push(piece);
+        }
+        prefix[field].retain(|v| q[field].contains(v));
+    }
+    pieces
+}
+
+fn is_subset(p: &Product, q: &Product) -> bool {
+    (0..5).all(|field| p[field].iter().all(|v| q[field].contains(v)))
+}
+
+/// Drops products that others cover and merges products that differ in a
+/// single field, until neither is possible.
+fn reduce(mut products: Vec<Product>) -> Vec<Product> {
+    loop {
+        let covered = (0..products.len()).find(|&i| {
+            (0..products.len()).any(|j| {
+                i != j
+                    && is_subset(&products[i], &products[j])
+                    && (products[i] != products[j] || i > j)
+            })
+        });
+        if let Some(i) = covered {
+            products.remove(i);
+            continue;
+        }
+
+        let mergeable = (0..products.len()).find_map(|i| {
+            (i + 1..products.len()).find_map(|j| {
+                let differing = (0..5)
+                    .filter(|&field| products[i][field] != products[j][field])
+                    .collect::<Vec<_>>();
+                match differing.as_slice() {
+                    [field] => Some((i, j, *field)),
+                    _ => None,
+                }
+            })
+        });
+        match mergeable {
+            Some((i, j, field)) => {
+                let other = products.remove(j);
+                products[i][field].extend(other[field].iter());
+                products[i][field].sort();
+                products[i][field].dedup();
+            }
+            None => return products,
+        }
+    }
+}
+
+fn value(product: &Product, field: usize) -> Value {
+    Value::from_values(&product[field], BOUNDS[field].0, BOUNDS[field].1)
+}
+
+/// The product's day field if it's naturally written starting with `*`,
+/// like `*/2`, which makes a line run only on days both day fields select.
+fn starred(product: &Product, field: usize) -> Option<Value> {
+    Some(value(product, field)).filter(|value| value.starts_with_star())
+}
+
+/// Writes products as cron lines. A line can restrict either the day of the
+/// month or the day of the week, or both if the same times are also allowed
+/// on every day of the other kind. Days that must match both fields can only
+/// be written when one of them is a step starting with `*`.
+fn to_schedules(mut products: Vec<Product>) -> Option<Vec<Schedule>> {
+    let schedule = |product: &Product, day_of_month: Value, day_of_week: Value| Schedule {
+        minute: Minute {
+            value: value(product, 0),
+        },
+        hour: Hour {
+            value: value(product, 1),
+        },
+        day_of_month: DayOfMonth {
+            value: day_of_month,
+        },
+        month: Month {
+            value: value(product, 3),
+        },
+        day_of_week: DayOfWeek { value: day_of_week },
+    };
+
+    let mut schedules = Vec::new();
+    while let Some(p) = products.pop() {
+        if !is_full(&p, DAY_OF_MONTH) && !is_full(&p, DAY_OF_WEEK) {
+            let line = match (starred(&p, DAY_OF_MONTH), starred(&p, DAY_OF_WEEK)) {
+                (Some(day_of_month), _) => schedule(&p, day_of_month, value(&p, DAY_OF_WEEK)),
+                (None, Some(day_of_week)) => schedule(&p, value(&p, DAY_OF_MONTH), day_of_week),
+                (None, None) => return None,
+            };
+            schedules.push(line);
+            continue;
+        }
+        // A product restricting the day of the month and one restricting
+        // the day of the week at the same times make a single line.
+        let by_day_of_month = |x: &Product| !is_full(x, DAY_OF_MONTH) && is_full(x, DAY_OF_WEEK);
+        let by_day_of_week = |x: &Product| is_full(x, DAY_OF_MONTH) && !is_full(x, DAY_OF_WEEK);
+        let partner = products.iter().position(|q| {
+            [0, 1, 3].iter().all(|&field| p[field] == q[field])
+                && (by_day_of_month(&p) && by_day_of_week(q)
+                    || by_day_of_week(&p) && by_day_of_month(q))
+        });
+        match partner {
+            Some(i) => {
+                let q = products.remove(i);
+                let (by_day_of_month, by_day_of_week) = if by_day_of_month(&p) {
+                    (&p, &q)
+                } else {
+                    (&q, &p)
+                };
+                // Neither field may start with `*`, or the line would only
+                // run on days both select.
+                schedules.push(schedule(
+                    &p,
+                    value(by_day_of_month, DAY_OF_MONTH).without_star(BOUNDS[DAY_OF_MONTH].0),
+                    value(by_day_of_week, DAY_OF_WEEK).without_star(BOUNDS[DAY_OF_WEEK].0),
+                ));
+            }
+            None => schedules.push(schedule(
+                &p,
+                value(&p, DAY_OF_MONTH),
+                value(&p, DAY_OF_WEEK),
+            )),
+        }
+    }
+    schedules.reverse();
+    Some(schedules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn lines(expression: &str) -> Option<Vec<String>> {
+        ScheduleSet::from_str(expression)
+            .unwrap()
+            .to_cron_lines()
+            .map(|lines| lines.iter().map(|l| l.to_string()).collect())
+    }
+
+    #[test]
+    fn parses_operators() {
+        let set = ScheduleSet::from_str("0 * * * * or 30 * * * * except 0 2 * * *").unwrap();
+
+        assert_eq!(
+            set.to_string(),
+            "(0 * * * * or 30 * * * *) except 0 2 * * *"
+        );
+        assert!(ScheduleSet::from_str("0 * * * * or").is_err());
+    }
+
+    #[test]
+    fn every_90_minutes() {
+        let set = ScheduleSet::from_str("0 */3 * * * or 30 1-22/3 * * *").unwrap();
+
+        assert_eq!(
+            set.occurrences(datetime!(2023-03-22 00:00:00 +0:00:00))
+                .take(4)
+                .collect::<Vec<_>>(),
+            vec![
+                datetime!(2023-03-22 00:00:00 +0:00:00),
+                datetime!(2023-03-22 01:30:00 +0:00:00),
+                datetime!(2023-03-22 03:00:00 +0:00:00),
+                datetime!(2023-03-22 04:30:00 +0:00:00),
+            ]
+        );
+        assert_eq!(
+            lines("0 */3 * * * or 30 1-22/3 * * *").unwrap(),
+            vec!["0 */3 * * *", "30 1/3 * * *"]
+        );
+        assert_eq!(
+            set.describe(&DescribeOptions::default()),
+            "At minute 0 past every 3rd hour and at minute 30 past every 3rd hour from 1 through 22."
+        );
+    }
+
+    #[test]
+    fn except_backup() {
+        let set = ScheduleSet::from_str("0 * * * * except 0 2 * * *").unwrap();
+
+        assert!(set.matches(datetime!(2023-03-22 01:00:00 +0:00:00)));
+        assert!(!set.matches(datetime!(2023-03-22 02:00:00 +0:00:00)));
+        assert_eq!(
+            set.next_occurrence(datetime!(2023-03-22 01:30:00 +0:00:00)),
+            Ok(datetime!(2023-03-22 03:00:00 +0:00:00))
+        );
+        assert_eq!(
+            lines("0 * * * * except 0 2 * * *").unwrap(),
+            vec!["0 0,1,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23 * * *"]
+        );
+        assert_eq!(
+            set.describe(&DescribeOptions::default()),
+            "At minute 0, except at 02:00."
+        );
+    }
+
+    #[test]
+    fn simplifies() {
+        assert_eq!(
+            lines("0 * * * 1-5 and 0 9-17 * * *").unwrap(),
+            vec!["0 9-17 * * 1-5"]
+        );
+        assert_eq!(
+            lines("0 9 * * MON or 0 9 * * TUE").unwrap(),
+            vec!["0 9 * * 1-2"]
+        );
+        assert_eq!(
+            lines("0 9 * * MON or 0 9 * * *").unwrap(),
+            vec!["0 9 * * *"]
+        );
+        assert_eq!(lines("0 9 1 * MON").unwrap(), vec!["0 9 1 * 1"]);
+        // `*/2` would only run on Mondays that are odd days.
+        assert_eq!(
+            lines("0 9 1/2 * * or 0 9 * * MON").unwrap(),
+            vec!["0 9 1/2 * 1"]
+        );
+        assert_eq!(lines("0 9 */2 * MON").unwrap(), vec!["0 9 */2 * 1"]);
+        assert_eq!(
+            lines("0 9 * * * except 0-30 * * * *").unwrap(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn not_cron_lines() {
+        // The 1st of the month, but only if it's a Monday.
+        let set = ScheduleSet::from_str("0 0 1 * * and 0 0 * * MON").unwrap();
+
+        assert_eq!(set.to_cron_lines(), None);
+        assert_eq!(
+            lines("0 0 */2 * * and 0 0 * * MON").unwrap(),
+            vec!["0 0 */2 * 1"]
+        );
+        assert_eq!(
+            set.next_occurrence(datetime!(2023-03-22 00:00:00 +0:00:00)),
+            Ok(datetime!(2023-05-01 00:00:00 +0:00:00))
+        );
+    }
+}
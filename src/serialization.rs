@@ -0,0 +1,209 @@
+//! Serde support, enabled with the `serde` feature.
+//!
+//! A [`Schedule`] serializes as its string, like `"30 9 * * 1-5"`, and
+//! deserializes from one with the same validation as [`Schedule::from_str`].
+//! Use [`structured`] to store the parsed fields instead:
+//!
+//! ```
+//! # use explaincron::cron::Schedule;
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Job {
+//!     #[serde(with = "explaincron::serialization::structured")]
+//!     schedule: Schedule,
+//! }
+//! ```
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::cron::{DayOfMonth, DayOfWeek, Hour, Minute, Month, Schedule, Value};
+
+impl Serialize for Schedule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Schedule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Schedule, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Schedule::from_str(&s).map_err(D::Error::custom)
+    }
+}
+
+/// A field as it's stored, with the same keys as the `--output json` fields.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum StoredValue {
+    Step {
+        start: Option<i32>,
+        step: i32,
+    },
+    Range {
+        start: i32,
+        stop: i32,
+        step: Option<i32>,
+    },
+    List {
+        items: Vec<i32>,
+    },
+    Single {
+        value: i32,
+    },
+    Wildcard,
+}
+
+impl From<&Value> for StoredValue {
+    fn from(value: &Value) -> StoredValue {
+        match value.clone() {
+            Value::Step(start, step) => StoredValue::Step { start, step },
+            Value::Range(start, stop, step) => StoredValue::Range { start, stop, step },
+            Value::List(items) => StoredValue::List { items },
+            Value::Single(value) => StoredValue::Single { value },
+            Value::Wildcard => StoredValue::Wildcard,
+        }
+    }
+}
+
+impl From<StoredValue> for Value {
+    fn from(value: StoredValue) -> Value {
+        match value {
+            StoredValue::Step { start, step } => Value::Step(start, step),
+            StoredValue::Range { start, stop, step } => Value::Range(start, stop, step),
+            StoredValue::List { items } => Value::List(items),
+            StoredValue::Single { value } => Value::Single(value),
+            StoredValue::Wildcard => Value::Wildcard,
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StoredValue::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        StoredValue::deserialize(deserializer).map(Value::from)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredSchedule {
+    minute: Value,
+    hour: Value,
+    day_of_month: Value,
+    month: Value,
+    day_of_week: Value,
+}
+
+/// Stores a schedule as its parsed fields, like
+/// `{"minute": {"kind": "single", "value": 30}, ...}`, for use with
+/// `#[serde(with = "...")]`.
+pub mod structured {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(schedule: &Schedule, serializer: S) -> Result<S::Ok, S::Error> {
+        StoredSchedule {
+            minute: schedule.minute.value.clone(),
+            hour: schedule.hour.value.clone(),
+            day_of_month: schedule.day_of_month.value.clone(),
+            month: schedule.month.value.clone(),
+            day_of_week: schedule.day_of_week.value.clone(),
+        }
+        .serialize(serializer)
+    }
+
+    /// Checks the fields like the parser would, by parsing them as written.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Schedule, D::Error> {
+        let stored = StoredSchedule::deserialize(deserializer)?;
+        let schedule = Schedule {
+            minute: Minute {
+                value: stored.minute,
+            },
+            hour: Hour { value: stored.hour },
+            day_of_month: DayOfMonth {
+                value: stored.day_of_month,
+            },
+            month: Month {
+                value: stored.month,
+            },
+            day_of_week: DayOfWeek {
+                value: stored.day_of_week,
+            },
+        };
+        Schedule::from_str(&schedule.to_string()).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Job {
+        #[serde(with = "structured")]
+        schedule: Schedule,
+    }
+
+    #[test]
+    fn schedule_as_string() {
+        let schedule = Schedule::from_str("*/15 9-17 * * MON").unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&schedule).unwrap(),
+            r#""*/15 9-17 * * 1""#
+        );
+        assert_eq!(
+            serde_json::from_str::<Schedule>(r#""*/15 9-17 * * 1""#).unwrap(),
+            schedule
+        );
+        assert_eq!(
+            serde_json::from_str::<Schedule>(r#""0 24 * * *""#)
+                .unwrap_err()
+                .to_string(),
+            "'24' is not a valid hour (0-23)"
+        );
+    }
+
+    #[test]
+    fn sunday_round_trips() {
+        for input in ["0 0 * * SUN", "0 0 * * MON-SUN", "0 0 * * 0,SUN"] {
+            let job = Job {
+                schedule: Schedule::from_str(input).unwrap(),
+            };
+            let json = serde_json::to_string(&job.schedule).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Schedule>(&json).unwrap(),
+                job.schedule
+            );
+            let json = serde_json::to_string(&job).unwrap();
+            assert_eq!(serde_json::from_str::<Job>(&json).unwrap(), job);
+        }
+    }
+
+    #[test]
+    fn structured_schedule() {
+        let job = Job {
+            schedule: Schedule::from_str("30 9 * * 1-5").unwrap(),
+        };
+        let json = serde_json::to_string(&job).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"schedule":{"minute":{"kind":"single","value":30},"hour":{"kind":"single","value":9},"day_of_month":{"kind":"wildcard"},"month":{"kind":"wildcard"},"day_of_week":{"kind":"range","start":1,"stop":5,"step":null}}}"#
+        );
+        assert_eq!(serde_json::from_str::<Job>(&json).unwrap(), job);
+    }
+
+    #[test]
+    fn structured_schedule_is_validated() {
+        let json = r#"{"schedule":{"minute":{"kind":"list","items":[0,60]},"hour":{"kind":"wildcard"},"day_of_month":{"kind":"wildcard"},"month":{"kind":"wildcard"},"day_of_week":{"kind":"wildcard"}}}"#;
+
+        assert!(serde_json::from_str::<Job>(json)
+            .unwrap_err()
+            .to_string()
+            .starts_with("'60' is not a valid minute (0-59)"));
+    }
+}
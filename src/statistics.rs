@@ -0,0 +1,210 @@
+use time::macros::date;
+use time::Duration;
+
+use crate::cron::{Days, Schedule};
+
+// The calendar repeats every 400 years, which is also a whole number of
+// weeks, so counting over one such cycle is exact.
+const CYCLE_DAYS: usize = 146_097;
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// The fewest, most and average runs in a period.
+#[derive(Debug, PartialEq)]
+pub struct Count {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+}
+
+impl Count {
+    fn of(counts: impl Iterator<Item = usize> + Clone) -> Count {
+        let (total, periods) = counts.clone().fold((0, 0), |(t, n), c| (t + c, n + 1));
+        Count {
+            min: counts.clone().min().unwrap_or(0),
+            max: counts.max().unwrap_or(0),
+            mean: total as f64 / periods as f64,
+        }
+    }
+}
+
+pub struct Statistics {
+    pub per_hour: Count,
+    pub per_day: Count,
+    pub per_week: Count,
+    pub per_month: Count,
+    pub per_year: Count,
+    pub min_gap: Duration,
+    pub max_gap: Duration,
+    pub mean_gap: Duration,
+}
+
+impl Statistics {
+    /// Whether every run follows the previous one after the same time.
+    pub fn is_uniform(&self) -> bool {
+        self.min_gap == self.max_gap
+    }
+}
+
+/// How often the schedule runs, worked out from its fields over a whole
+/// 400-year cycle of the calendar, in UTC. Nothing if it never runs.
+pub fn statistics(schedule: &Schedule) -> Option<Statistics> {
+    let hours = schedule.hour.value.values(0, 23);
+    let mut times = hours
+        .iter()
+        .flat_map(|hour| {
+            schedule
+                .minute
+                .value
+                .values(0, 59)
+                .into_iter()
+                .map(move |minute| (hour * 60 + minute) as i64)
+        })
+        .collect::<Vec<_>>();
+    times.sort();
+    let per_running_day = times.len();
+
+    // From 2000-01-01, a Saturday.
+    let running = Days::new(schedule);
+    let days = std::iter::successors(Some(date!(2000 - 01 - 01)), |d| d.next_day())
+        .take(CYCLE_DAYS)
+        .collect::<Vec<_>>();
+    let runs = days
+        .iter()
+        .map(|day| {
+            if running.contains(*day) {
+                per_running_day
+            } else {
+                0
+            }
+        })
+        .collect::<Vec<_>>();
+    let running_days = (0..CYCLE_DAYS).filter(|i| runs[*i] > 0).collect::<Vec<_>>();
+    if running_days.is_empty() {
+        return None;
+    }
+
+    let per_hour = Count {
+        min: if running_days.len() == CYCLE_DAYS && hours.len() == 24 {
+            per_running_day / 24
+        } else {
+            0
+        },
+        max: per_running_day / hours.len(),
+        mean: (per_running_day * running_days.len()) as f64 / (CYCLE_DAYS * 24) as f64,
+    };
+    // Weeks from Monday, 2000-01-03, wrapping around the cycle.
+    let per_week = Count::of((0..CYCLE_DAYS / 7).map(|week| {
+        (0..7)
+            .map(|day| runs[(2 + week * 7 + day) % CYCLE_DAYS])
+            .sum()
+    }));
+    let per_period = |same: fn(time::Date, time::Date) -> bool| {
+        let mut counts = Vec::new();
+        for (i, day) in days.iter().enumerate() {
+            if i == 0 || !same(days[i - 1], *day) {
+                counts.push(0);
+            }
+            *counts.last_mut().unwrap() += runs[i];
+        }
+        Count::of(counts.into_iter())
+    };
+
+    // Consecutive runs are either on the same day, or the last one of a day
+    // and the first one of the next day the schedule runs on.
+    let same_day_gaps = times.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>();
+    let day_gaps = running_days
+        .windows(2)
+        .map(|w| (w[1] - w[0]) as i64)
+        .chain([(CYCLE_DAYS - running_days.last().unwrap() + running_days[0]) as i64])
+        .collect::<Vec<_>>();
+    let overnight = |days: i64| days * MINUTES_PER_DAY - times.last().unwrap() + times[0];
+    let min_gap = same_day_gaps
+        .iter()
+        .copied()
+        .chain([overnight(*day_gaps.iter().min().unwrap())])
+        .min()
+        .unwrap();
+    let max_gap = same_day_gaps
+        .iter()
+        .copied()
+        .chain([overnight(*day_gaps.iter().max().unwrap())])
+        .max()
+        .unwrap();
+    let total_runs = per_running_day * running_days.len();
+
+    Some(Statistics {
+        per_hour,
+        per_day: Count::of(runs.iter().copied()),
+        per_week,
+        per_month: per_period(|a, b| a.month() == b.month()),
+        per_year: per_period(|a, b| a.year() == b.year()),
+        min_gap: Duration::minutes(min_gap),
+        max_gap: Duration::minutes(max_gap),
+        mean_gap: Duration::seconds((CYCLE_DAYS as i64 * MINUTES_PER_DAY * 60) / total_runs as i64),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statistics_of(schedule: &str) -> Statistics {
+        statistics(&Schedule::from_str(schedule).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn steps_reset_every_hour() {
+        let result = statistics_of("*/7 * * * *");
+
+        assert_eq!(result.min_gap, Duration::minutes(4));
+        assert_eq!(result.max_gap, Duration::minutes(7));
+        assert!(!result.is_uniform());
+        assert_eq!(result.per_hour.min, 9);
+        assert_eq!(result.per_hour.max, 9);
+        assert_eq!(result.per_day.max, 216);
+    }
+
+    #[test]
+    fn uniform_cadence() {
+        let result = statistics_of("*/15 * * * *");
+
+        assert!(result.is_uniform());
+        assert_eq!(result.mean_gap, Duration::minutes(15));
+        assert_eq!(
+            result.per_week,
+            Count {
+                min: 672,
+                max: 672,
+                mean: 672.0
+            }
+        );
+    }
+
+    #[test]
+    fn weekdays() {
+        let result = statistics_of("30 9 * * 1-5");
+
+        assert_eq!(result.per_week.min, 5);
+        assert_eq!(result.per_week.max, 5);
+        assert_eq!(result.per_day.min, 0);
+        assert_eq!(result.per_month.min, 20);
+        assert_eq!(result.per_month.max, 23);
+        assert_eq!(result.per_year.min, 260);
+        assert_eq!(result.per_year.max, 262);
+        assert!((result.per_year.mean - 260.8875).abs() < 1e-9);
+        assert_eq!(result.min_gap, Duration::days(1));
+        assert_eq!(result.max_gap, Duration::days(3));
+        assert_eq!(result.mean_gap, Duration::minutes(7 * 24 * 60 / 5));
+    }
+
+    #[test]
+    fn leap_days() {
+        let result = statistics_of("0 0 29 2 *");
+
+        assert_eq!(result.per_year.min, 0);
+        assert_eq!(result.per_year.max, 1);
+        assert_eq!(result.min_gap, Duration::days(4 * 365 + 1));
+        assert_eq!(result.max_gap, Duration::days(8 * 365 + 1));
+        assert!(statistics(&Schedule::from_str("0 0 30 2 *").unwrap()).is_none());
+    }
+}
@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+
+use time::ext::NumericalDuration;
+use time::{Duration, OffsetDateTime, Time};
+
+use crate::cron::{self, DayOfMonth, DayOfWeek, Hour, Minute, Month, Schedule, Value};
+
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+/// Runs at a fixed interval, like every 90 minutes.
+pub struct Cadence {
+    pub interval: Duration,
+    /// A time the cadence runs at, in minutes since midnight. The other runs
+    /// of the day are whole intervals before and after it.
+    pub anchor: i32,
+    /// The first and last time of day to run, in minutes since midnight.
+    pub window: Option<(i32, i32)>,
+}
+
+/// A gap between runs that isn't the interval.
+pub struct Irregularity {
+    pub gap: Duration,
+    pub after: OffsetDateTime,
+}
+
+pub struct Synthesis {
+    pub lines: Vec<Schedule>,
+    /// Each different wrong gap found, with the first run it follows.
+    pub irregularities: Vec<Irregularity>,
+}
+
+impl Synthesis {
+    pub fn is_exact(&self) -> bool {
+        self.irregularities.is_empty()
+    }
+}
+
+/// Parses a time of day like `08:10` into minutes since midnight.
+pub fn parse_time_of_day(input: &str) -> Result<i32, String> {
+    let err = || format!("'{input}' is not a valid time of day (e.g. 08:10)");
+    let (hour, minute) = input.split_once(':').ok_or_else(err)?;
+    let hour = hour.parse::<i32>().map_err(|_| err())?;
+    let minute = minute.parse::<i32>().map_err(|_| err())?;
+    if !(0..24).contains(&hour) || !(0..60).contains(&minute) {
+        return Err(err());
+    }
+    Ok(hour * 60 + minute)
+}
+
+/// Parses a window of the day like `08:00-18:00`.
+pub fn parse_window(input: &str) -> Result<(i32, i32), String> {
+    let (start, end) = input
+        .split_once('-')
+        .ok_or_else(|| format!("'{input}' is not a valid window (e.g. 08:00-18:00)"))?;
+    let (start, end) = (parse_time_of_day(start)?, parse_time_of_day(end)?);
+    if start > end {
+        return Err(format!("window '{input}' ends before it starts"));
+    }
+    Ok((start, end))
+}
+
+impl Cadence {
+    /// Finds the fewest cron lines for the cadence, and checks their runs
+    /// from the day of `from_time`. Cron restarts its steps every day (and
+    /// month), so unless the interval fits into those evenly, the lines
+    /// only approximate the cadence and the irregular gaps are returned.
+    pub fn synthesize(&self, from_time: OffsetDateTime) -> Result<Synthesis, String> {
+        if self.interval <= Duration::ZERO || self.interval.whole_seconds() % 60 != 0 {
+            return Err("the interval must be a positive number of minutes".to_string());
+        }
+        // Intervals too long for an i32 are over 31 days anyway.
+        let step = i32::try_from(self.interval.whole_minutes()).unwrap_or(i32::MAX);
+
+        let (lines, times, span) = if step < MINUTES_PER_DAY {
+            let (start, end) = self.window.unwrap_or((0, MINUTES_PER_DAY - 1));
+            let times = (start..=end)
+                .filter(|time| (time - self.anchor).rem_euclid(step) == 0)
+                .collect::<Vec<_>>();
+            if times.is_empty() {
+                return Err(
+                    "no time in the window is a whole number of intervals from the anchor"
+                        .to_string(),
+                );
+            }
+            (times_of_day(&times), times, 2.days())
+        } else {
+            if step % MINUTES_PER_DAY != 0 || step > 31 * MINUTES_PER_DAY {
+                return Err("intervals over a day must be whole days, up to 31".to_string());
+            }
+            if self.window.is_some() {
+                return Err("a window only applies to intervals under a day".to_string());
+            }
+            let days = step / MINUTES_PER_DAY;
+            let line = |day_of_month, day_of_week| Schedule {
+                minute: Minute {
+                    value: Value::Single(self.anchor % 60),
+                },
+                hour: Hour {
+                    value: Value::Single(self.anchor / 60),
+                },
+                day_of_month: DayOfMonth {
+                    value: day_of_month,
+                },
+                month: Month {
+                    value: Value::Wildcard,
+                },
+                day_of_week: DayOfWeek { value: day_of_week },
+            };
+            let line = match days {
+                1 => line(Value::Wildcard, Value::Wildcard),
+                // Weekly runs stay on the weekday of `from_time`.
+                7 => line(
+                    Value::Wildcard,
+                    Value::Single(from_time.weekday().number_days_from_sunday() as i32),
+                ),
+                _ => line(Value::Step(None, days), Value::Wildcard),
+            };
+            (vec![line], vec![self.anchor], 366.days())
+        };
+
+        self.verify(lines, &times, from_time.replace_time(Time::MIDNIGHT), span)
+    }
+
+    /// Checks that the lines run at `times` of the day, and no others, and
+    /// collects the gaps that aren't the interval.
+    fn verify(
+        &self,
+        lines: Vec<Schedule>,
+        times: &[i32],
+        start: OffsetDateTime,
+        span: Duration,
+    ) -> Result<Synthesis, String> {
+        let end = start + span;
+        let mut runs = Vec::new();
+        let mut next = start;
+        loop {
+            next = lines
+                .iter()
+                .map(|line| cron::next_occurrence(next, line))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .min()
+                .unwrap();
+            if next >= end {
+                break;
+            }
+            runs.push(next);
+            next = next.saturating_add(1.seconds());
+        }
+
+        let first_day = runs
+            .iter()
+            .take_while(|run| run.date() == runs[0].date())
+            .map(|run| run.hour() as i32 * 60 + run.minute() as i32)
+            .collect::<Vec<_>>();
+        if first_day != times {
+            let lines = lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+            return Err(format!(
+                "'{}' don't run at the intended times",
+                lines.join("' and '")
+            ));
+        }
+
+        let mut irregularities: Vec<Irregularity> = Vec::new();
+        for pair in runs.windows(2) {
+            // The gap overnight is intended when running within a window.
+            if self.window.is_some() && pair[0].date() != pair[1].date() {
+                continue;
+            }
+            let gap = pair[1] - pair[0];
+            if gap != self.interval && irregularities.iter().all(|i| i.gap != gap) {
+                irregularities.push(Irregularity {
+                    gap,
+                    after: pair[0],
+                });
+            }
+        }
+
+        Ok(Synthesis {
+            lines,
+            irregularities,
+        })
+    }
+}
+
+/// The fewest lines running at the times of day, either as lines of
+/// minutes that share their hours or lines of hours that share their
+/// minutes.
+pub fn times_of_day(times: &[i32]) -> Vec<Schedule> {
+    let mut minutes_by_hours = BTreeMap::<Vec<i32>, Vec<i32>>::new();
+    for minute in 0..60 {
+        let hours = times
+            .iter()
+            .filter(|time| *time % 60 == minute)
+            .map(|time| time / 60)
+            .collect::<Vec<_>>();
+        if !hours.is_empty() {
+            minutes_by_hours.entry(hours).or_default().push(minute);
+        }
+    }
+    let mut hours_by_minutes = BTreeMap::<Vec<i32>, Vec<i32>>::new();
+    for hour in 0..24 {
+        let minutes = times
+            .iter()
+            .filter(|time| *time / 60 == hour)
+            .map(|time| time % 60)
+            .collect::<Vec<_>>();
+        if !minutes.is_empty() {
+            hours_by_minutes.entry(minutes).or_default().push(hour);
+        }
+    }
+
+    let mut lines = if hours_by_minutes.len() < minutes_by_hours.len() {
+        hours_by_minutes.into_iter().collect::<Vec<_>>()
+    } else {
+        minutes_by_hours
+            .into_iter()
+            .map(|(hours, minutes)| (minutes, hours))
+            .collect()
+    };
+    // Earliest first.
+    lines.sort_by_key(|(minutes, hours)| (hours[0], minutes[0]));
+    lines
+        .into_iter()
+        .map(|(minutes, hours)| Schedule {
+            minute: Minute {
+                value: Value::from_values(&minutes, 0, 59),
+            },
+            hour: Hour {
+                value: Value::from_values(&hours, 0, 23),
+            },
+            day_of_month: DayOfMonth {
+                value: Value::Wildcard,
+            },
+            month: Month {
+                value: Value::Wildcard,
+            },
+            day_of_week: DayOfWeek {
+                value: Value::Wildcard,
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn synthesize(interval: Duration, anchor: &str, window: Option<&str>) -> Synthesis {
+        Cadence {
+            interval,
+            anchor: parse_time_of_day(anchor).unwrap(),
+            window: window.map(|w| parse_window(w).unwrap()),
+        }
+        .synthesize(datetime!(2023-03-22 10:00:00 +0:00:00))
+        .unwrap()
+    }
+
+    fn lines(synthesis: &Synthesis) -> Vec<String> {
+        synthesis.lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn exact_cadences() {
+        let result = synthesize(90.minutes(), "00:00", None);
+        assert_eq!(lines(&result), vec!["0 */3 * * *", "30 1/3 * * *"]);
+        assert!(result.is_exact());
+
+        let result = synthesize(40.minutes(), "08:10", None);
+        assert_eq!(lines(&result), vec!["10,50 */2 * * *", "30 1/2 * * *"]);
+        assert!(result.is_exact());
+
+        let result = synthesize(30.minutes(), "09:00", Some("09:00-17:00"));
+        assert_eq!(lines(&result), vec!["0 9-17 * * *", "30 9-16 * * *"]);
+        assert!(result.is_exact());
+
+        let result = synthesize(7.days(), "06:00", None);
+        assert_eq!(lines(&result), vec!["0 6 * * 3"]);
+        assert!(result.is_exact());
+    }
+
+    #[test]
+    fn approximations() {
+        let result = synthesize(7.minutes(), "00:00", None);
+        assert_eq!(result.lines.len(), 7);
+        assert_eq!(result.irregularities.len(), 1);
+        assert_eq!(result.irregularities[0].gap, 5.minutes());
+        assert_eq!(
+            result.irregularities[0].after,
+            datetime!(2023-03-22 23:55:00 +0:00:00)
+        );
+
+        let result = synthesize(2.days(), "00:00", None);
+        assert_eq!(lines(&result), vec!["0 0 */2 * *"]);
+        assert_eq!(result.irregularities[0].gap, 1.days());
+        assert_eq!(
+            result.irregularities[0].after,
+            datetime!(2023-03-31 00:00:00 +0:00:00)
+        );
+    }
+
+    #[test]
+    fn invalid_cadences() {
+        let cadence = |interval, window| Cadence {
+            interval,
+            anchor: 0,
+            window,
+        };
+        let from_time = datetime!(2023-03-22 10:00:00 +0:00:00);
+
+        assert!(cadence(90.seconds(), None).synthesize(from_time).is_err());
+        assert!(cadence(36.hours(), None).synthesize(from_time).is_err());
+        assert!(cadence(999_999_999_999.weeks(), None)
+            .synthesize(from_time)
+            .is_err());
+        assert!(cadence(2.hours(), Some((60, 90)))
+            .synthesize(from_time)
+            .is_err());
+        assert!(parse_window("18:00-08:00").is_err());
+        assert!(parse_time_of_day("24:00").is_err());
+    }
+}